@@ -1,8 +1,13 @@
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
-use syn::{Error, ItemFn, LitStr};
+use syn::meta::ParseNestedMeta;
+use syn::{Error, ItemFn, LitInt, LitStr};
 
-pub fn parse_entry(entry: ItemFn, options: EntryOptions) -> Result<TokenStream, Error> {
+pub fn parse_entry(
+    entry: ItemFn,
+    ty: EntryType,
+    options: EntryOptions,
+) -> Result<TokenStream, Error> {
     // Get debug writer.
     let debug = match options.debug {
         Some(v) => match v {
@@ -22,24 +27,103 @@ pub fn parse_entry(entry: ItemFn, options: EntryOptions) -> Result<TokenStream,
         None => quote!(None),
     };
 
+    // Disable watchdog timer.
+    let watchdog = if options.disable_watchdog {
+        quote!(let _ = ::zfi::disable_watchdog();)
+    } else {
+        TokenStream::new()
+    };
+
     // Get panic handler.
     let panic = if options.no_ph {
+        if let Some(v) = options.reboot_on_panic {
+            return Err(Error::new(
+                v.span(),
+                "`reboot_on_panic` cannot be used together with `no_ph`",
+            ));
+        }
+
         TokenStream::new()
+    } else if let Some(v) = options.reboot_on_panic {
+        quote! {
+            /// A panic handler that was generated by `zfi::main` macro.
+            #[cfg(not(test))]
+            #[panic_handler]
+            fn panic_handler(info: &::core::panic::PanicInfo) -> ! {
+                ::zfi::eprintln!("{info}");
+
+                if !::zfi::boot_services_exited() {
+                    ::zfi::sleep(::core::time::Duration::from_secs(#v));
+                }
+
+                ::zfi::reset(::zfi::ResetType::Cold, ::zfi::Status::ABORTED, None)
+            }
+        }
     } else {
+        // A driver cannot exit once its entry point has been returned.
+        let abort = match ty {
+            EntryType::Application => quote!(::zfi::exit(::zfi::Status::ABORTED, None)),
+            EntryType::Driver => quote!(::zfi::abort_driver()),
+        };
+
         quote! {
             /// A panic handler that was generated by `zfi::main` macro.
             #[cfg(not(test))]
             #[panic_handler]
             fn panic_handler(info: &::core::panic::PanicInfo) -> ! {
                 ::zfi::eprintln!("{info}");
-                loop {}
+                #abort
             }
         }
     };
 
-    // Compose fragments.
+    // Get allocator.
+    let allocator = {
+        let ty = options.allocator.memory_type.unwrap_or_else(|| {
+            let name = match ty {
+                EntryType::Application => "LoaderData",
+                EntryType::Driver => "BootServicesData",
+            };
+
+            Ident::new(name, Span::call_site())
+        });
+        let mut flags = quote!(::zfi::PoolOptions::empty());
+
+        if options.allocator.stats {
+            flags.extend(quote!(.union(::zfi::PoolOptions::STATS)));
+        }
+
+        if options.allocator.debug {
+            flags.extend(quote!(.union(::zfi::PoolOptions::DEBUG)));
+        }
+
+        quote!(::zfi::PoolAllocator::new(::zfi::MemoryType::#ty, #flags))
+    };
+
+    // Get the body of the real entry point.
     let name = &entry.sig.ident;
+    let body = match ty {
+        EntryType::Application => quote! {
+            let f: fn() -> ::zfi::Status = #name;
+            unsafe { ::zfi::init(image, st, #debug) };
+            #watchdog
+            ::zfi::exit(f(), None)
+        },
+        EntryType::Driver => quote! {
+            let f: fn() -> ::core::result::Result<
+                ::alloc::boxed::Box<dyn ::zfi::DriverBinding>,
+                ::zfi::Status,
+            > = #name;
+            unsafe { ::zfi::init(image, st, #debug) };
+            #watchdog
+            match f().and_then(::zfi::install_driver) {
+                Ok(_) => ::zfi::Status::SUCCESS,
+                Err(e) => ::zfi::exit(e, None),
+            }
+        },
+    };
 
+    // Compose fragments.
     Ok(quote! {
         extern crate alloc;
 
@@ -49,9 +133,7 @@ pub fn parse_entry(entry: ItemFn, options: EntryOptions) -> Result<TokenStream,
             image: &'static ::zfi::Image,
             st: &'static ::zfi::SystemTable,
         ) -> ::zfi::Status {
-            let f: fn() -> ::zfi::Status = #name;
-            unsafe { ::zfi::init(image, st, #debug) };
-            f()
+            #body
         }
 
         #entry
@@ -61,7 +143,7 @@ pub fn parse_entry(entry: ItemFn, options: EntryOptions) -> Result<TokenStream,
         /// A global allocator that was generated by `zfi::main` macro.
         #[cfg(not(test))]
         #[global_allocator]
-        static ALLOCATOR: ::zfi::PoolAllocator = ::zfi::PoolAllocator;
+        static ALLOCATOR: ::zfi::PoolAllocator = #allocator;
     })
 }
 
@@ -69,6 +151,59 @@ pub fn parse_entry(entry: ItemFn, options: EntryOptions) -> Result<TokenStream,
 pub struct EntryOptions {
     pub debug: Option<EntryDebug>,
     pub no_ph: bool,
+    pub disable_watchdog: bool,
+    pub allocator: EntryAllocator,
+    pub reboot_on_panic: Option<LitInt>,
+}
+
+impl EntryOptions {
+    pub fn parse(&mut self, m: ParseNestedMeta) -> syn::Result<()> {
+        if m.path.is_ident("debug_extension") {
+            self.debug = Some(EntryDebug::Extension(m.value()?.parse()?));
+        } else if m.path.is_ident("debug_writer") {
+            self.debug = Some(EntryDebug::Writer(m.value()?.parse()?));
+        } else if m.path.is_ident("no_ph") {
+            self.no_ph = true;
+        } else if m.path.is_ident("disable_watchdog") {
+            self.disable_watchdog = true;
+        } else if m.path.is_ident("reboot_on_panic") {
+            self.reboot_on_panic = Some(m.value()?.parse()?);
+        } else if m.path.is_ident("allocator") {
+            m.parse_nested_meta(|m| self.allocator.parse(m))?;
+        } else {
+            return Err(m.error("unknown option"));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct EntryAllocator {
+    pub memory_type: Option<Ident>,
+    pub stats: bool,
+    pub debug: bool,
+}
+
+impl EntryAllocator {
+    fn parse(&mut self, m: ParseNestedMeta) -> syn::Result<()> {
+        if m.path.is_ident("memory_type") {
+            self.memory_type = Some(m.value()?.parse()?);
+        } else if m.path.is_ident("stats") {
+            self.stats = true;
+        } else if m.path.is_ident("debug") {
+            self.debug = true;
+        } else {
+            return Err(m.error("unknown allocator option"));
+        }
+
+        Ok(())
+    }
+}
+
+pub enum EntryType {
+    Application,
+    Driver,
 }
 
 pub enum EntryDebug {
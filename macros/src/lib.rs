@@ -1,6 +1,6 @@
 use self::entry::parse_entry;
 use self::string::parse_str;
-use crate::entry::{EntryDebug, EntryOptions};
+use crate::entry::{EntryOptions, EntryType};
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, Error, ItemFn, LitStr};
 
@@ -22,27 +22,53 @@ mod string;
 /// Other available options:
 ///
 /// - `no_ph`: Do not generate `panic_handler`.
+/// - `disable_watchdog`: Disable the watchdog timer before invoking the entry. Without this option
+///   the firmware will reset the system if the application does not return within 5 minutes.
+/// - `reboot_on_panic`: An integer literal that specify the number of seconds to wait before
+///   rebooting the system when panic instead of exiting the image (e.g.
+///   `#[zfi::main(reboot_on_panic = 10)]`). The debug writer will be dropped before rebooting. This
+///   cannot be used together with `no_ph`.
+/// - `allocator`: Configure the generated `zfi::PoolAllocator` (e.g.
+///   `#[zfi::main(allocator(memory_type = BootServicesData, stats, debug))]`). `memory_type` is a
+///   variant of `zfi::MemoryType` (default to `LoaderData` for [`macro@main`] and
+///   `BootServicesData` for [`macro@driver`]), `stats` enable the statistics and `debug` enable the
+///   heap corruption detection and the leak reporting on the debug log.
 #[proc_macro_attribute]
 pub fn main(arg: TokenStream, item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as ItemFn);
     let mut options = EntryOptions::default();
-    let parser = syn::meta::parser(|m| {
-        if m.path.is_ident("debug_extension") {
-            options.debug = Some(EntryDebug::Extension(m.value()?.parse()?));
-        } else if m.path.is_ident("debug_writer") {
-            options.debug = Some(EntryDebug::Writer(m.value()?.parse()?));
-        } else if m.path.is_ident("no_ph") {
-            options.no_ph = true;
-        } else {
-            return Err(m.error("unknown option"));
-        }
-
-        Ok(())
-    });
+    let parser = syn::meta::parser(|m| options.parse(m));
 
     parse_macro_input!(arg with parser);
 
-    parse_entry(item, options)
+    parse_entry(item, EntryType::Application, options)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Define the entry of EFI boot service driver, automatically import `alloc` crate, generate
+/// `global_allocator` and `panic_handler`.
+///
+/// The function must return `Result<alloc::boxed::Box<dyn zfi::DriverBinding>, zfi::Status>`. The
+/// returned value will be installed with `zfi::install_driver()` and the driver will stay in the
+/// memory until it is unloaded.
+///
+/// This macro accept the same options as [`macro@main`] except the global allocator will use
+/// `BootServicesData` by default. The generated panic handler will call `zfi::abort_driver()`
+/// instead of exiting the image, which will hang the system if the panic occurs after the driver
+/// has been installed (e.g. inside `DriverBinding::start()`). Specify `reboot_on_panic` if you want
+/// to reboot the system instead. You also need to pass `/subsystem:efi_boot_service_driver` to the
+/// linker otherwise the firmware will treat the image as an application (e.g.
+/// `-C link-args=/subsystem:efi_boot_service_driver`).
+#[proc_macro_attribute]
+pub fn driver(arg: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    let mut options = EntryOptions::default();
+    let parser = syn::meta::parser(|m| options.parse(m));
+
+    parse_macro_input!(arg with parser);
+
+    parse_entry(item, EntryType::Driver, options)
         .unwrap_or_else(Error::into_compile_error)
         .into()
 }
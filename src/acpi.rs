@@ -0,0 +1,873 @@
+use crate::{system_table, Guid};
+use core::mem::size_of;
+use core::ptr::read_unaligned;
+use core::slice::from_raw_parts;
+use thiserror::Error;
+
+/// Represents an ACPI Root System Description Pointer.
+///
+/// The fields that were introduced in ACPI 2.0 will be zero for ACPI 1.0.
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct Rsdp {
+    signature: [u8; 8],
+    checksum: u8,
+    oem_id: [u8; 6],
+    revision: u8,
+    rsdt_address: u32,
+    length: u32,
+    xsdt_address: u64,
+    extended_checksum: u8,
+    reserved: [u8; 3],
+}
+
+impl Rsdp {
+    /// GUID of the configuration table for ACPI 1.0.
+    pub const ACPI1_GUID: Guid = Guid::new(
+        0xeb9d2d30,
+        0x2d88,
+        0x11d3,
+        [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+    );
+
+    /// GUID of the configuration table for ACPI 2.0 or later.
+    pub const ACPI2_GUID: Guid = Guid::new(
+        0x8868e871,
+        0xe4f1,
+        0x11d3,
+        [0xbc, 0x22, 0x00, 0x80, 0xc7, 0x3c, 0x88, 0x81],
+    );
+
+    /// Size of ACPI 1.0 RSDP.
+    const V1_LEN: usize = 20;
+
+    /// Locates the RSDP from the configuration table. ACPI 2.0 will be preferred if the firmware
+    /// provides both versions.
+    pub fn find() -> Result<Self, AcpiError> {
+        let st = system_table();
+        let ptr = st
+            .find_config_table(&Self::ACPI2_GUID)
+            .or_else(|| st.find_config_table(&Self::ACPI1_GUID))
+            .ok_or(AcpiError::NotFound)?;
+
+        unsafe { Self::from_ptr(ptr.cast()) }
+    }
+
+    /// Validates the RSDP at `ptr` and its root table (RSDT or XSDT). Returns a copy of the RSDP.
+    ///
+    /// # Safety
+    /// `ptr` must point to a readable memory that is large enough for the RSDP and all ACPI tables
+    /// must be identity mapped.
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, AcpiError> {
+        let rsdp = Self::parse(ptr)?;
+
+        // Check the root table.
+        let (root, sig) = match rsdp.xsdt() {
+            Some(v) => (v, b"XSDT"),
+            None => (rsdp.rsdt().ok_or(AcpiError::NotFound)?, b"RSDT"),
+        };
+
+        if root.signature() != *sig {
+            return Err(AcpiError::InvalidSignature);
+        } else if !root.is_valid() {
+            return Err(AcpiError::InvalidChecksum);
+        }
+
+        Ok(rsdp)
+    }
+
+    /// Reads and validates the RSDP at `ptr` without checking its root table.
+    ///
+    /// # Safety
+    /// `ptr` must point to a readable memory that is large enough for the RSDP.
+    unsafe fn parse(ptr: *const u8) -> Result<Self, AcpiError> {
+        // Check ACPI 1.0 part. We can't read beyond this until we know the revision.
+        let v1 = from_raw_parts(ptr, Self::V1_LEN);
+
+        if &v1[..8] != b"RSD PTR " {
+            return Err(AcpiError::InvalidSignature);
+        } else if !checksum(v1) {
+            return Err(AcpiError::InvalidChecksum);
+        } else if v1[15] < 2 {
+            return Ok(Self {
+                signature: v1[..8].try_into().unwrap(),
+                checksum: v1[8],
+                oem_id: v1[9..15].try_into().unwrap(),
+                revision: v1[15],
+                rsdt_address: u32::from_le_bytes(v1[16..].try_into().unwrap()),
+                length: 0,
+                xsdt_address: 0,
+                extended_checksum: 0,
+                reserved: [0; 3],
+            });
+        }
+
+        // Check ACPI 2.0 part.
+        let rsdp = read_unaligned(ptr.cast::<Self>());
+        let len = rsdp.length as usize;
+
+        if len < size_of::<Self>() {
+            return Err(AcpiError::InvalidLength);
+        } else if !checksum(from_raw_parts(ptr, len)) {
+            return Err(AcpiError::InvalidChecksum);
+        }
+
+        Ok(rsdp)
+    }
+
+    /// Returns 0 for ACPI 1.0 or 2 for ACPI 2.0 and later.
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    pub fn oem_id(&self) -> [u8; 6] {
+        self.oem_id
+    }
+
+    /// Returns the root table. This will be XSDT if available otherwise RSDT.
+    pub fn root(&self) -> &SdtHeader {
+        // Both tables was validated by from_ptr() so one of it must be available.
+        match self.xsdt() {
+            Some(v) => v,
+            None => self.rsdt().unwrap(),
+        }
+    }
+
+    /// Returns an iterator over the tables that are listed in the root table.
+    ///
+    /// The iterator does not validate the tables. Use [`SdtHeader::is_valid()`] to validate it.
+    pub fn tables(&self) -> AcpiTables<'_> {
+        let root = self.root();
+        let size = match &root.signature() {
+            b"XSDT" => 8,
+            _ => 4,
+        };
+
+        AcpiTables {
+            entries: root.data(),
+            size,
+        }
+    }
+
+    /// Returns the first valid table that has the same signature as `T`.
+    pub fn table<T: AcpiTable>(&self) -> Option<&T> {
+        self.tables().find_map(|t| t.cast())
+    }
+
+    fn rsdt(&self) -> Option<&SdtHeader> {
+        unsafe { SdtHeader::from_addr(self.rsdt_address.into()) }
+    }
+
+    fn xsdt(&self) -> Option<&SdtHeader> {
+        if self.revision < 2 {
+            return None;
+        }
+
+        unsafe { SdtHeader::from_addr(self.xsdt_address) }
+    }
+}
+
+/// An iterator over the tables that are listed in RSDT or XSDT.
+pub struct AcpiTables<'a> {
+    entries: &'a [u8],
+    size: usize,
+}
+
+impl<'a> Iterator for AcpiTables<'a> {
+    type Item = &'a SdtHeader;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Get the address.
+            let addr = self.entries.get(..self.size)?;
+            let addr = match self.size {
+                8 => u64::from_le_bytes(addr.try_into().unwrap()),
+                _ => u32::from_le_bytes(addr.try_into().unwrap()).into(),
+            };
+
+            self.entries = &self.entries[self.size..];
+
+            // Skip a null entry.
+            if let Some(v) = unsafe { SdtHeader::from_addr(addr) } {
+                break Some(v);
+            }
+        }
+    }
+}
+
+/// Represents a header of ACPI System Description Table.
+#[repr(C, packed)]
+pub struct SdtHeader {
+    signature: [u8; 4],
+    length: u32,
+    revision: u8,
+    checksum: u8,
+    oem_id: [u8; 6],
+    oem_table_id: [u8; 8],
+    oem_revision: u32,
+    creator_id: u32,
+    creator_revision: u32,
+}
+
+impl SdtHeader {
+    /// # Safety
+    /// `addr` must be zero or an address of a valid table.
+    unsafe fn from_addr<'a>(addr: u64) -> Option<&'a Self> {
+        match usize::try_from(addr) {
+            Ok(0) | Err(_) => None,
+            Ok(v) => Some(&*(v as *const Self)),
+        }
+    }
+
+    pub fn signature(&self) -> [u8; 4] {
+        self.signature
+    }
+
+    /// Returns the length of the whole table, including the header.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    pub fn oem_id(&self) -> [u8; 6] {
+        self.oem_id
+    }
+
+    pub fn oem_table_id(&self) -> [u8; 8] {
+        self.oem_table_id
+    }
+
+    pub fn oem_revision(&self) -> u32 {
+        self.oem_revision
+    }
+
+    pub fn creator_id(&self) -> u32 {
+        self.creator_id
+    }
+
+    pub fn creator_revision(&self) -> u32 {
+        self.creator_revision
+    }
+
+    /// Returns `true` if the length and the checksum of this table is valid.
+    pub fn is_valid(&self) -> bool {
+        (self.length as usize) >= size_of::<Self>() && checksum(self.as_bytes())
+    }
+
+    /// Returns the whole table, including the header.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = (self.length as usize).max(size_of::<Self>());
+
+        unsafe { from_raw_parts(self as *const Self as *const u8, len) }
+    }
+
+    /// Returns the data after the header.
+    pub fn data(&self) -> &[u8] {
+        &self.as_bytes()[size_of::<Self>()..]
+    }
+
+    /// Casts this table to `T`. Returns [`None`] if the signature is not matched, the table is
+    /// invalid or it is too small for `T`.
+    pub fn cast<T: AcpiTable>(&self) -> Option<&T> {
+        if self.signature != T::SIGNATURE || !self.is_valid() || (self.length as usize) < T::MIN_LEN
+        {
+            return None;
+        }
+
+        Some(unsafe { &*(self as *const Self as *const T) })
+    }
+
+    fn read<const N: usize>(&self, off: usize) -> Option<[u8; N]> {
+        self.as_bytes().get(off..(off + N))?.try_into().ok()
+    }
+
+    fn u8(&self, off: usize) -> Option<u8> {
+        self.read::<1>(off).map(|v| v[0])
+    }
+
+    fn u16(&self, off: usize) -> Option<u16> {
+        self.read(off).map(u16::from_le_bytes)
+    }
+
+    fn u32(&self, off: usize) -> Option<u32> {
+        self.read(off).map(u32::from_le_bytes)
+    }
+
+    fn u64(&self, off: usize) -> Option<u64> {
+        self.read(off).map(u64::from_le_bytes)
+    }
+
+    fn gas(&self, off: usize) -> Option<GenericAddress> {
+        self.read(off).map(GenericAddress::new)
+    }
+}
+
+/// A typed view of an ACPI table.
+///
+/// # Safety
+/// The type must be `#[repr(transparent)]` over [`SdtHeader`].
+pub unsafe trait AcpiTable {
+    const SIGNATURE: [u8; 4];
+
+    /// Minimum length of the table, including the header.
+    const MIN_LEN: usize;
+}
+
+/// Represents a Multiple APIC Description Table (MADT).
+#[repr(transparent)]
+pub struct Madt(SdtHeader);
+
+impl Madt {
+    pub fn header(&self) -> &SdtHeader {
+        &self.0
+    }
+
+    /// Returns the physical address of the local interrupt controller of each processor.
+    pub fn local_apic_address(&self) -> u32 {
+        self.0.u32(36).unwrap()
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.0.u32(40).unwrap()
+    }
+
+    /// Returns an iterator over the interrupt controller structures.
+    pub fn entries(&self) -> MadtEntries<'_> {
+        MadtEntries(&self.0.as_bytes()[Self::MIN_LEN..])
+    }
+}
+
+unsafe impl AcpiTable for Madt {
+    const SIGNATURE: [u8; 4] = *b"APIC";
+    const MIN_LEN: usize = 44;
+}
+
+/// An iterator over the interrupt controller structures in [`Madt`].
+pub struct MadtEntries<'a>(&'a [u8]);
+
+impl<'a> Iterator for MadtEntries<'a> {
+    type Item = MadtEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Get the entry.
+        let ty = *self.0.first()?;
+        let len = usize::from(*self.0.get(1)?);
+
+        if len < 2 || len > self.0.len() {
+            self.0 = &[];
+            return None;
+        }
+
+        let data = &self.0[..len];
+
+        self.0 = &self.0[len..];
+
+        // Parse the entry.
+        Some(MadtEntry::parse(ty, data).unwrap_or(MadtEntry::Unknown { ty, data }))
+    }
+}
+
+/// An interrupt controller structure in [`Madt`].
+#[derive(Debug, Clone, Copy)]
+pub enum MadtEntry<'a> {
+    LocalApic {
+        processor_id: u8,
+        apic_id: u8,
+        flags: u32,
+    },
+    IoApic {
+        id: u8,
+        address: u32,
+        gsi_base: u32,
+    },
+    InterruptSourceOverride {
+        bus: u8,
+        source: u8,
+        gsi: u32,
+        flags: u16,
+    },
+    NmiSource {
+        flags: u16,
+        gsi: u32,
+    },
+    LocalApicNmi {
+        processor_id: u8,
+        flags: u16,
+        lint: u8,
+    },
+    LocalApicAddressOverride {
+        address: u64,
+    },
+    LocalX2Apic {
+        x2apic_id: u32,
+        flags: u32,
+        processor_uid: u32,
+    },
+    LocalX2ApicNmi {
+        flags: u16,
+        processor_uid: u32,
+        lint: u8,
+    },
+    Gicc {
+        cpu_interface_number: u32,
+        processor_uid: u32,
+        flags: u32,
+        physical_base_address: u64,
+        gicr_base_address: u64,
+        mpidr: u64,
+    },
+    Gicd {
+        id: u32,
+        physical_base_address: u64,
+        version: u8,
+    },
+    Unknown {
+        ty: u8,
+        data: &'a [u8],
+    },
+}
+
+impl MadtEntry<'_> {
+    fn parse(ty: u8, data: &[u8]) -> Option<Self> {
+        let u8 = |off: usize| data.get(off).copied();
+        let u16 = |off: usize| {
+            data.get(off..(off + 2))
+                .map(|v| u16::from_le_bytes(v.try_into().unwrap()))
+        };
+        let u32 = |off: usize| {
+            data.get(off..(off + 4))
+                .map(|v| u32::from_le_bytes(v.try_into().unwrap()))
+        };
+        let u64 = |off: usize| {
+            data.get(off..(off + 8))
+                .map(|v| u64::from_le_bytes(v.try_into().unwrap()))
+        };
+        let entry = match ty {
+            0 => Self::LocalApic {
+                processor_id: u8(2)?,
+                apic_id: u8(3)?,
+                flags: u32(4)?,
+            },
+            1 => Self::IoApic {
+                id: u8(2)?,
+                address: u32(4)?,
+                gsi_base: u32(8)?,
+            },
+            2 => Self::InterruptSourceOverride {
+                bus: u8(2)?,
+                source: u8(3)?,
+                gsi: u32(4)?,
+                flags: u16(8)?,
+            },
+            3 => Self::NmiSource {
+                flags: u16(2)?,
+                gsi: u32(4)?,
+            },
+            4 => Self::LocalApicNmi {
+                processor_id: u8(2)?,
+                flags: u16(3)?,
+                lint: u8(5)?,
+            },
+            5 => Self::LocalApicAddressOverride { address: u64(4)? },
+            9 => Self::LocalX2Apic {
+                x2apic_id: u32(4)?,
+                flags: u32(8)?,
+                processor_uid: u32(12)?,
+            },
+            10 => Self::LocalX2ApicNmi {
+                flags: u16(2)?,
+                processor_uid: u32(4)?,
+                lint: u8(8)?,
+            },
+            11 => Self::Gicc {
+                cpu_interface_number: u32(4)?,
+                processor_uid: u32(8)?,
+                flags: u32(12)?,
+                physical_base_address: u64(32)?,
+                gicr_base_address: u64(60)?,
+                mpidr: u64(68)?,
+            },
+            12 => Self::Gicd {
+                id: u32(4)?,
+                physical_base_address: u64(8)?,
+                version: u8(20)?,
+            },
+            _ => return None,
+        };
+
+        Some(entry)
+    }
+}
+
+/// Represents a Fixed ACPI Description Table (FADT).
+#[repr(transparent)]
+pub struct Fadt(SdtHeader);
+
+impl Fadt {
+    pub fn header(&self) -> &SdtHeader {
+        &self.0
+    }
+
+    /// Returns the physical address of the FACS. The 64-bit address will be preferred if
+    /// available.
+    pub fn firmware_ctrl(&self) -> u64 {
+        match self.0.u64(132) {
+            Some(v) if v != 0 => v,
+            _ => self.0.u32(36).unwrap().into(),
+        }
+    }
+
+    /// Returns the physical address of the DSDT. The 64-bit address will be preferred if
+    /// available.
+    pub fn dsdt_address(&self) -> u64 {
+        match self.0.u64(140) {
+            Some(v) if v != 0 => v,
+            _ => self.0.u32(40).unwrap().into(),
+        }
+    }
+
+    /// Returns the DSDT or [`None`] if it is not available.
+    pub fn dsdt(&self) -> Option<&SdtHeader> {
+        unsafe { SdtHeader::from_addr(self.dsdt_address()) }
+    }
+
+    pub fn preferred_pm_profile(&self) -> u8 {
+        self.0.u8(45).unwrap()
+    }
+
+    pub fn sci_interrupt(&self) -> u16 {
+        self.0.u16(46).unwrap()
+    }
+
+    pub fn smi_command(&self) -> u32 {
+        self.0.u32(48).unwrap()
+    }
+
+    pub fn pm_timer_block(&self) -> u32 {
+        self.0.u32(76).unwrap()
+    }
+
+    pub fn century(&self) -> u8 {
+        self.0.u8(108).unwrap()
+    }
+
+    pub fn iapc_boot_arch(&self) -> u16 {
+        self.0.u16(109).unwrap()
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.0.u32(112).unwrap()
+    }
+
+    /// Returns [`None`] if the table is ACPI 1.0.
+    pub fn reset_register(&self) -> Option<GenericAddress> {
+        self.0.gas(116)
+    }
+
+    /// Returns [`None`] if the table is ACPI 1.0.
+    pub fn reset_value(&self) -> Option<u8> {
+        self.0.u8(128)
+    }
+
+    /// Returns [`None`] if the table is older than ACPI 5.1.
+    pub fn arm_boot_arch(&self) -> Option<u16> {
+        self.0.u16(129)
+    }
+
+    /// Returns [`None`] if the table is older than ACPI 5.1.
+    pub fn minor_version(&self) -> Option<u8> {
+        self.0.u8(131)
+    }
+}
+
+unsafe impl AcpiTable for Fadt {
+    const SIGNATURE: [u8; 4] = *b"FACP";
+    const MIN_LEN: usize = 116;
+}
+
+/// Represents an IA-PC High Precision Event Timer Table (HPET).
+#[repr(transparent)]
+pub struct Hpet(SdtHeader);
+
+impl Hpet {
+    pub fn header(&self) -> &SdtHeader {
+        &self.0
+    }
+
+    pub fn event_timer_block_id(&self) -> u32 {
+        self.0.u32(36).unwrap()
+    }
+
+    pub fn base_address(&self) -> GenericAddress {
+        self.0.gas(40).unwrap()
+    }
+
+    pub fn hpet_number(&self) -> u8 {
+        self.0.u8(52).unwrap()
+    }
+
+    /// Returns the minimum clock ticks can be set without lost interrupts while the counter is
+    /// programmed to operate in periodic mode.
+    pub fn minimum_tick(&self) -> u16 {
+        self.0.u16(53).unwrap()
+    }
+
+    pub fn page_protection(&self) -> u8 {
+        self.0.u8(55).unwrap()
+    }
+}
+
+unsafe impl AcpiTable for Hpet {
+    const SIGNATURE: [u8; 4] = *b"HPET";
+    const MIN_LEN: usize = 56;
+}
+
+/// Represents a PCI Express Memory-mapped Configuration Space base address description table
+/// (MCFG).
+#[repr(transparent)]
+pub struct Mcfg(SdtHeader);
+
+impl Mcfg {
+    pub fn header(&self) -> &SdtHeader {
+        &self.0
+    }
+
+    /// Returns an iterator over the configuration space base address allocation structures.
+    pub fn entries(&self) -> impl Iterator<Item = McfgEntry> + '_ {
+        self.0.as_bytes()[Self::MIN_LEN..]
+            .chunks_exact(16)
+            .map(|v| McfgEntry {
+                base_address: u64::from_le_bytes(v[..8].try_into().unwrap()),
+                segment: u16::from_le_bytes(v[8..10].try_into().unwrap()),
+                start_bus: v[10],
+                end_bus: v[11],
+            })
+    }
+}
+
+unsafe impl AcpiTable for Mcfg {
+    const SIGNATURE: [u8; 4] = *b"MCFG";
+    const MIN_LEN: usize = 44;
+}
+
+/// A configuration space base address allocation structure in [`Mcfg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct McfgEntry {
+    base_address: u64,
+    segment: u16,
+    start_bus: u8,
+    end_bus: u8,
+}
+
+impl McfgEntry {
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    pub fn segment(&self) -> u16 {
+        self.segment
+    }
+
+    pub fn start_bus(&self) -> u8 {
+        self.start_bus
+    }
+
+    pub fn end_bus(&self) -> u8 {
+        self.end_bus
+    }
+}
+
+/// Represents a Boot Graphics Resource Table (BGRT).
+#[repr(transparent)]
+pub struct Bgrt(SdtHeader);
+
+impl Bgrt {
+    pub fn header(&self) -> &SdtHeader {
+        &self.0
+    }
+
+    pub fn version(&self) -> u16 {
+        self.0.u16(36).unwrap()
+    }
+
+    pub fn status(&self) -> u8 {
+        self.0.u8(38).unwrap()
+    }
+
+    /// Returns 0 if the image is a bitmap.
+    pub fn image_type(&self) -> u8 {
+        self.0.u8(39).unwrap()
+    }
+
+    pub fn image_address(&self) -> u64 {
+        self.0.u64(40).unwrap()
+    }
+
+    pub fn image_offset_x(&self) -> u32 {
+        self.0.u32(48).unwrap()
+    }
+
+    pub fn image_offset_y(&self) -> u32 {
+        self.0.u32(52).unwrap()
+    }
+}
+
+unsafe impl AcpiTable for Bgrt {
+    const SIGNATURE: [u8; 4] = *b"BGRT";
+    const MIN_LEN: usize = 56;
+}
+
+/// Represents an ACPI Generic Address Structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenericAddress {
+    address_space: u8,
+    bit_width: u8,
+    bit_offset: u8,
+    access_size: u8,
+    address: u64,
+}
+
+impl GenericAddress {
+    fn new(data: [u8; 12]) -> Self {
+        Self {
+            address_space: data[0],
+            bit_width: data[1],
+            bit_offset: data[2],
+            access_size: data[3],
+            address: u64::from_le_bytes(data[4..].try_into().unwrap()),
+        }
+    }
+
+    /// Returns 0 for system memory space or 1 for system I/O space.
+    pub fn address_space(&self) -> u8 {
+        self.address_space
+    }
+
+    pub fn bit_width(&self) -> u8 {
+        self.bit_width
+    }
+
+    pub fn bit_offset(&self) -> u8 {
+        self.bit_offset
+    }
+
+    pub fn access_size(&self) -> u8 {
+        self.access_size
+    }
+
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+}
+
+/// Represents an error when [`Rsdp::find()`] or [`Rsdp::from_ptr()`] is failed.
+#[derive(Debug, Error)]
+pub enum AcpiError {
+    #[error("no ACPI tables")]
+    NotFound,
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("invalid checksum")]
+    InvalidChecksum,
+}
+
+fn checksum(data: &[u8]) -> bool {
+    data.iter().fold(0u8, |s, &v| s.wrapping_add(v)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rsdp_v1() {
+        let data = rsdp(0, 0x12345678);
+        let mut v1 = [0u8; Rsdp::V1_LEN];
+
+        v1.copy_from_slice(&data[..Rsdp::V1_LEN]);
+
+        let rsdp = unsafe { Rsdp::parse(v1.as_ptr()).unwrap() };
+
+        assert_eq!(rsdp.revision(), 0);
+        assert_eq!(rsdp.oem_id(), *b"ZFIOEM");
+        assert_eq!({ rsdp.rsdt_address }, 0x12345678);
+        assert_eq!({ rsdp.length }, 0);
+        assert!(rsdp.xsdt().is_none());
+
+        // Corrupt the checksum.
+        v1[Rsdp::V1_LEN - 1] ^= 1;
+
+        assert!(matches!(
+            unsafe { Rsdp::parse(v1.as_ptr()) },
+            Err(AcpiError::InvalidChecksum)
+        ));
+    }
+
+    #[test]
+    fn rsdp_v2() {
+        // Setup XSDT without any entries.
+        let mut xsdt = [0u8; 36];
+
+        xsdt[..4].copy_from_slice(b"XSDT");
+        xsdt[4..8].copy_from_slice(&36u32.to_le_bytes());
+        xsdt[9] = fix(&xsdt);
+
+        // Check.
+        let data = rsdp(2, xsdt.as_ptr() as u64);
+        let rsdp = unsafe { Rsdp::from_ptr(data.as_ptr()).unwrap() };
+
+        assert_eq!(rsdp.revision(), 2);
+        assert_eq!({ rsdp.length }, 36);
+        assert_eq!(rsdp.root().signature(), *b"XSDT");
+        assert_eq!(rsdp.tables().count(), 0);
+
+        // Corrupt the extended checksum.
+        let mut data = data;
+
+        data[33] = 1;
+
+        assert!(matches!(
+            unsafe { Rsdp::parse(data.as_ptr()) },
+            Err(AcpiError::InvalidChecksum)
+        ));
+
+        // Corrupt the signature.
+        data[0] = b'r';
+
+        assert!(matches!(
+            unsafe { Rsdp::parse(data.as_ptr()) },
+            Err(AcpiError::InvalidSignature)
+        ));
+    }
+
+    fn rsdp(revision: u8, root: u64) -> [u8; 36] {
+        let mut data = [0u8; 36];
+
+        data[..8].copy_from_slice(b"RSD PTR ");
+        data[9..15].copy_from_slice(b"ZFIOEM");
+        data[15] = revision;
+
+        if revision < 2 {
+            data[16..20].copy_from_slice(&(root as u32).to_le_bytes());
+        } else {
+            data[20..24].copy_from_slice(&36u32.to_le_bytes());
+            data[24..32].copy_from_slice(&root.to_le_bytes());
+        }
+
+        data[8] = fix(&data[..20]);
+        data[32] = fix(&data);
+        data
+    }
+
+    fn fix(data: &[u8]) -> u8 {
+        0u8.wrapping_sub(data.iter().fold(0u8, |s, &v| s.wrapping_add(v)))
+    }
+}
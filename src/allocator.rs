@@ -1,55 +1,298 @@
-use crate::{system_table, MemoryType};
+use crate::{boot_services_exited, heap_alloc, system_table, MemoryType};
+use bitflags::bitflags;
 use core::alloc::{GlobalAlloc, Layout};
+use core::cmp::min;
 use core::mem::size_of;
-use core::ptr::{null_mut, read_unaligned, write_unaligned};
+use core::ptr::{null_mut, read_unaligned, write_bytes, write_unaligned, NonNull};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+static DEBUG_POOL: AtomicPtr<PoolAllocator> = AtomicPtr::new(null_mut());
+
+const MAGIC: usize = 0x5A46495F;
+const GUARD: u8 = 0xFD;
+const GUARD_LEN: usize = 16;
+const FREED: u8 = 0xDD;
 
 /// An implementation of [`GlobalAlloc`] using EFI memory pool.
-pub struct PoolAllocator;
+///
+/// Once [`crate::exit_boot_services()`] has been called this allocator will allocate from the heap
+/// that was reserved by [`crate::exit_boot_services()`] and deallocation will do nothing.
+pub struct PoolAllocator {
+    ty: MemoryType,
+    options: PoolOptions,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    count: AtomicUsize,
+    baseline: AtomicUsize,
+}
+
+impl PoolAllocator {
+    /// Creates a new allocator that allocates `ty` from the pool.
+    pub const fn new(ty: MemoryType, options: PoolOptions) -> Self {
+        Self {
+            ty,
+            options,
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            count: AtomicUsize::new(0),
+            baseline: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the type of memory to allocate.
+    pub fn memory_type(&self) -> MemoryType {
+        self.ty
+    }
+
+    /// Returns the options that was passed to [`Self::new()`].
+    pub fn options(&self) -> PoolOptions {
+        self.options
+    }
+
+    /// Returns the statistics of this allocator. All values will be zero if neither
+    /// [`PoolOptions::STATS`] nor [`PoolOptions::DEBUG`] is enabled.
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            live: self.live.load(Ordering::Relaxed),
+            peak: self.peak.load(Ordering::Relaxed),
+            allocations: self.count.load(Ordering::Relaxed),
+        }
+    }
+
+    fn tracking(&self) -> bool {
+        self.options
+            .intersects(PoolOptions::STATS | PoolOptions::DEBUG)
+    }
+
+    fn debug(&self) -> bool {
+        self.options.contains(PoolOptions::DEBUG)
+    }
+
+    /// Checks the guard bytes of the allocation at `ptr`.
+    ///
+    /// # Panics
+    /// If the guard bytes was overwritten.
+    unsafe fn check(&self, ptr: *mut u8, header: &Header, size: usize) {
+        let guard = core::slice::from_raw_parts(ptr.add(size), GUARD_LEN);
+
+        if header.magic != MAGIC || guard.iter().any(|&b| b != GUARD) {
+            panic!("heap corruption detected on the allocation at {ptr:p}");
+        }
+    }
+
+    unsafe fn resize(&self, ptr: *mut u8, layout: Layout, new_size: usize) {
+        if self.debug() {
+            write_bytes(ptr.add(new_size), GUARD, GUARD_LEN);
+        }
+
+        if self.tracking() {
+            let live = if new_size > layout.size() {
+                let v = new_size - layout.size();
+                self.live.fetch_add(v, Ordering::Relaxed) + v
+            } else {
+                self.live
+                    .fetch_sub(layout.size() - new_size, Ordering::Relaxed)
+            };
+
+            self.peak.fetch_max(live, Ordering::Relaxed);
+        }
+    }
+}
 
 unsafe impl GlobalAlloc for PoolAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        // Calculate allocation size to include a spare room for adjusting alignment.
-        let mut size = if layout.align() <= 8 {
-            layout.size()
-        } else {
-            layout.size() + (layout.align() - 8)
-        };
+        if boot_services_exited() {
+            return heap_alloc(layout);
+        }
 
-        // We will store how many bytes that we have shifted in the beginning at the end.
-        size += size_of::<usize>();
+        // Calculate allocation size to include the header and a spare room for adjusting
+        // alignment. The header size is a multiple of 8 so the memory after it will always be
+        // eight-byte aligned.
+        let guard = if self.debug() { GUARD_LEN } else { 0 };
+        let size = size_of::<Header>()
+            + layout.size()
+            + guard
+            + if layout.align() <= 8 {
+                0
+            } else {
+                layout.align() - 8
+            };
 
         // Do allocation.
         let mem = system_table()
             .boot_services()
-            .allocate_pool(MemoryType::LoaderData, size)
+            .allocate_pool(self.ty, size)
             .unwrap_or(null_mut());
 
         if mem.is_null() {
             return null_mut();
         }
 
-        // Get number of bytes to shift so the alignment is correct.
-        let misaligned = (mem as usize) % layout.align();
-        let adjust = if misaligned == 0 {
-            0
-        } else {
-            layout.align() - misaligned
+        // Get the aligned address.
+        let ptr = mem.add(size_of::<Header>());
+        let ptr = ptr.add(ptr.align_offset(layout.align()));
+        let offset = ptr.offset_from(mem) as usize;
+
+        // Write the header right before the aligned address.
+        let header = Header {
+            magic: MAGIC,
+            offset,
+            capacity: size - offset - guard,
         };
 
-        // Store how many bytes have been shifted.
-        let mem = mem.add(adjust);
+        write_unaligned(Header::of(ptr), header);
+
+        // Write the guard and update the statistics.
+        if self.debug() {
+            write_bytes(ptr.add(layout.size()), GUARD, GUARD_LEN);
+            DEBUG_POOL.store(self as *const Self as *mut Self, Ordering::Relaxed);
+        }
 
-        write_unaligned(mem.add(layout.size()) as *mut usize, adjust);
+        if self.tracking() {
+            let live = self.live.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+
+            self.peak.fetch_max(live, Ordering::Relaxed);
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
 
-        mem
+        ptr
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        // Get original address before alignment.
-        let adjusted = read_unaligned(ptr.add(layout.size()) as *const usize);
-        let ptr = ptr.sub(adjusted);
+        if boot_services_exited() {
+            return;
+        }
+
+        // Check the header.
+        let header = read_unaligned(Header::of(ptr));
+
+        if self.debug() {
+            self.check(ptr, &header, layout.size());
+            write_bytes(ptr, FREED, layout.size());
+        }
+
+        if self.tracking() {
+            self.live.fetch_sub(layout.size(), Ordering::Relaxed);
+            self.count.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        // Free the memory. There is nothing we can do if this failed.
+        let _ = system_table()
+            .boot_services()
+            .free_pool(ptr.sub(header.offset));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Reuse the same memory if it is large enough.
+        if !boot_services_exited() {
+            let header = read_unaligned(Header::of(ptr));
+
+            if self.debug() {
+                self.check(ptr, &header, layout.size());
+            }
+
+            if new_size <= header.capacity {
+                self.resize(ptr, layout, new_size);
+                return ptr;
+            }
+        }
+
+        // Allocate a new memory.
+        let new = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+
+        if !new.is_null() {
+            new.copy_from_nonoverlapping(ptr, min(layout.size(), new_size));
+            self.dealloc(ptr, layout);
+        }
+
+        new
+    }
+}
+
+bitflags! {
+    /// Options of [`PoolAllocator`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct PoolOptions: u32 {
+        /// Keep track of the statistics. See [`PoolAllocator::stats()`].
+        const STATS = 0x00000001;
+
+        /// Place guard bytes after each allocation to detect the heap corruption and report the
+        /// leaked allocations to the debug log when ZFI is terminating. This also enable
+        /// [`PoolOptions::STATS`].
+        ///
+        /// The leak report only excludes the allocations that were made by ZFI during
+        /// [`crate::init()`]. Any allocations that the debug writer made after that (e.g. growing
+        /// its buffer) will be reported as leaks.
+        const DEBUG = 0x00000002;
+    }
+}
+
+/// Statistics of [`PoolAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    live: usize,
+    peak: usize,
+    allocations: usize,
+}
+
+impl PoolStats {
+    /// Returns the number of bytes that currently allocated.
+    pub fn live(&self) -> usize {
+        self.live
+    }
+
+    /// Returns the highest value of [`Self::live()`].
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Returns the number of allocations that have not been freed.
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+}
+
+/// Marks all allocations up to this point as owned by ZFI so it will not be reported as a leak.
+pub(crate) fn set_leak_baseline() {
+    if let Some(p) = debug_pool() {
+        p.baseline
+            .store(p.count.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
+
+/// Writes the number of allocations that have not been freed to the debug log.
+pub(crate) fn report_leaks() {
+    let p = match debug_pool() {
+        Some(v) => v,
+        None => return,
+    };
+
+    let count = p.count.load(Ordering::Relaxed);
+    let baseline = p.baseline.load(Ordering::Relaxed);
+
+    if count > baseline {
+        crate::debugln!(
+            "{} allocation(s) have not been freed ({} bytes including ZFI allocations).",
+            count - baseline,
+            p.live.load(Ordering::Relaxed)
+        );
+    }
+}
+
+fn debug_pool() -> Option<&'static PoolAllocator> {
+    NonNull::new(DEBUG_POOL.load(Ordering::Relaxed)).map(|v| unsafe { v.as_ref() })
+}
+
+/// A header of each allocation, which located right before the returned pointer.
+#[repr(C, align(8))]
+struct Header {
+    magic: usize,
+    offset: usize, // From the beginning of the allocated memory to the returned pointer.
+    capacity: usize, // Bytes that can be used starting from the returned pointer.
+}
 
-        // Free the memory.
-        system_table().boot_services().free_pool(ptr).unwrap();
+impl Header {
+    fn of(ptr: *mut u8) -> *mut Self {
+        ptr.wrapping_sub(size_of::<Self>()).cast()
     }
 }
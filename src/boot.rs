@@ -1,7 +1,9 @@
-use crate::event::Event;
 use crate::{
-    current_image, Device, Guid, Image, Pages, Path, Status, TableHeader, IMAGE, PAGE_SIZE,
+    current_image, Device, Dtor, EfiStr, Event, EventNotify, EventType, ExitData, Guid, Image,
+    MemoryMap, Owned, Pages, Path, ProtocolNotify, RegionKind, Status, TableHeader, TimerDelay,
+    IMAGE, PAGE_SIZE,
 };
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 use bitflags::bitflags;
 use core::mem::size_of;
@@ -11,45 +13,65 @@ use core::ptr::{null, null_mut};
 #[repr(C)]
 pub struct BootServices {
     hdr: TableHeader,
-    raise_tpl: fn(),
-    restore_tpl: fn(),
+    raise_tpl: unsafe extern "efiapi" fn(Tpl) -> Tpl,
+    restore_tpl: unsafe extern "efiapi" fn(Tpl),
     allocate_pages: unsafe extern "efiapi" fn(AllocateType, MemoryType, usize, *mut u64) -> Status,
     free_pages: unsafe extern "efiapi" fn(u64, usize) -> Status,
-    get_memory_map: unsafe extern "efiapi" fn(
-        *mut usize,
-        *mut MemoryDescriptor,
-        *mut usize,
-        *mut usize,
-        *mut u32,
-    ) -> Status,
+    get_memory_map:
+        unsafe extern "efiapi" fn(*mut usize, *mut u8, *mut usize, *mut usize, *mut u32) -> Status,
     allocate_pool: unsafe extern "efiapi" fn(MemoryType, usize, *mut *mut u8) -> Status,
     free_pool: unsafe extern "efiapi" fn(*mut u8) -> Status,
-    create_event: fn(),
-    set_timer: fn(),
-    wait_for_event: unsafe extern "efiapi" fn(usize, *const Event, *mut usize) -> Status,
-    signal_event: fn(),
-    close_event: fn(),
-    check_event: fn(),
-    install_protocol_interface: fn(),
-    reinstall_protocol_interface: fn(),
-    uninstall_protocol_interface: fn(),
+    create_event: unsafe extern "efiapi" fn(
+        EventType,
+        Tpl,
+        Option<unsafe extern "efiapi" fn(&Event, *mut ())>,
+        *mut (),
+        *mut *mut Event,
+    ) -> Status,
+    set_timer: unsafe extern "efiapi" fn(&Event, TimerDelay, u64) -> Status,
+    wait_for_event: unsafe extern "efiapi" fn(usize, *const &Event, *mut usize) -> Status,
+    signal_event: unsafe extern "efiapi" fn(&Event) -> Status,
+    close_event: unsafe extern "efiapi" fn(*mut Event) -> Status,
+    check_event: unsafe extern "efiapi" fn(&Event) -> Status,
+    install_protocol_interface:
+        unsafe extern "efiapi" fn(*mut *const (), *const Guid, InterfaceType, *const ()) -> Status,
+    reinstall_protocol_interface:
+        unsafe extern "efiapi" fn(*const (), *const Guid, *const (), *const ()) -> Status,
+    uninstall_protocol_interface:
+        unsafe extern "efiapi" fn(*const (), *const Guid, *const ()) -> Status,
     handle_protocol: fn(),
     reserved: usize,
-    register_protocol_notify: fn(),
-    locate_handle: fn(),
+    register_protocol_notify:
+        unsafe extern "efiapi" fn(*const Guid, &Event, *mut *const ()) -> Status,
+    locate_handle: unsafe extern "efiapi" fn(
+        LocateSearchType,
+        *const Guid,
+        *const (),
+        *mut usize,
+        *mut &'static Device,
+    ) -> Status,
     locate_device_path:
         unsafe extern "efiapi" fn(*const Guid, *mut *const u8, *mut *const ()) -> Status,
-    install_configuration_table: fn(),
-    load_image: fn(),
-    start_image: fn(),
-    exit: fn(),
-    unload_image: fn(),
+    install_configuration_table: unsafe extern "efiapi" fn(*const Guid, *const ()) -> Status,
+    load_image: unsafe extern "efiapi" fn(
+        bool,
+        *const Image,
+        *const u8,
+        *const u8,
+        usize,
+        *mut *const Image,
+    ) -> Status,
+    start_image: unsafe extern "efiapi" fn(*const Image, *mut usize, *mut *mut u16) -> Status,
+    exit: unsafe extern "efiapi" fn(*const Image, Status, usize, *mut u16) -> Status,
+    unload_image: unsafe extern "efiapi" fn(*const Image) -> Status,
     exit_boot_services: extern "efiapi" fn(&Image, usize) -> Status,
-    get_next_monotonic_count: fn(),
-    stall: fn(),
-    set_watchdog_timer: fn(),
-    connect_controller: fn(),
-    disconnect_controller: fn(),
+    get_next_monotonic_count: unsafe extern "efiapi" fn(*mut u64) -> Status,
+    stall: extern "efiapi" fn(usize) -> Status,
+    set_watchdog_timer: unsafe extern "efiapi" fn(usize, u64, usize, *const u16) -> Status,
+    connect_controller:
+        unsafe extern "efiapi" fn(*const Device, *const *const Image, *const u8, bool) -> Status,
+    disconnect_controller:
+        unsafe extern "efiapi" fn(*const Device, *const Image, *const Device) -> Status,
     open_protocol: unsafe extern "efiapi" fn(
         *const (),
         *const Guid,
@@ -58,9 +80,43 @@ pub struct BootServices {
         *const (),
         OpenProtocolAttributes,
     ) -> Status,
+    close_protocol:
+        unsafe extern "efiapi" fn(*const (), *const Guid, *const (), *const ()) -> Status,
+    open_protocol_information: fn(),
+    protocols_per_handle:
+        unsafe extern "efiapi" fn(*const (), *mut *mut *const Guid, *mut usize) -> Status,
+    locate_handle_buffer: unsafe extern "efiapi" fn(
+        LocateSearchType,
+        *const Guid,
+        *const (),
+        *mut usize,
+        *mut *mut &'static Device,
+    ) -> Status,
+    locate_protocol: unsafe extern "efiapi" fn(*const Guid, *const (), *mut *const ()) -> Status,
+    install_multiple_protocol_interfaces: unsafe extern "C" fn(*mut *const (), ...) -> Status,
+    uninstall_multiple_protocol_interfaces: unsafe extern "C" fn(*const (), ...) -> Status,
 }
 
 impl BootServices {
+    /// Raises the task priority level and returns the previous one.
+    ///
+    /// Use [`crate::TplGuard`] instead if possible.
+    ///
+    /// # Safety
+    /// `tpl` must be the same or higher than the current task priority level and the previous level
+    /// must be restored with [`Self::restore_tpl()`].
+    pub unsafe fn raise_tpl(&self, tpl: Tpl) -> Tpl {
+        (self.raise_tpl)(tpl)
+    }
+
+    /// Restores the task priority level to the previous value.
+    ///
+    /// # Safety
+    /// `tpl` must be the value that was returned from [`Self::raise_tpl()`].
+    pub unsafe fn restore_tpl(&self, tpl: Tpl) {
+        (self.restore_tpl)(tpl)
+    }
+
     /// Allocates memory pages from the system.
     pub fn allocate_pages(
         &self,
@@ -91,44 +147,51 @@ impl BootServices {
     }
 
     /// Returns the current memory map. A common mistake when using this method to get a key to
-    /// invoke [`Self::exit_boot_services()`] is discarding the result, which will cause the vector
+    /// invoke [`Self::exit_boot_services()`] is discarding the result, which will cause the buffer
     /// to drop and memory map will be changed.
-    pub fn get_memory_map(&self) -> Result<(Vec<MemoryDescriptor>, usize), Status> {
-        let mut len = 1;
+    pub fn get_memory_map(&self) -> Result<MemoryMap, Status> {
+        let mut map = MemoryMap::new(0);
 
         loop {
-            let mut size = len * size_of::<MemoryDescriptor>();
-            let mut map: Vec<MemoryDescriptor> = Vec::with_capacity(len);
-            let mut key = 0;
-            let mut dsize = 0;
-            let mut dver = 0;
-            let status = unsafe {
-                (self.get_memory_map)(
-                    &mut size,
-                    map.spare_capacity_mut().as_mut_ptr() as _,
-                    &mut key,
-                    &mut dsize,
-                    &mut dver,
-                )
-            };
-
-            len = size / size_of::<MemoryDescriptor>();
+            let mut len = map.capacity();
 
-            match status {
+            match unsafe { self.read_memory_map_raw(&mut map, &mut len) } {
                 Status::SUCCESS => {
-                    assert_eq!(dsize, size_of::<MemoryDescriptor>());
-                    assert_eq!(dver, 1);
-
-                    unsafe { map.set_len(len) };
-
-                    break Ok((map, key));
+                    map.len = len;
+                    break Ok(map);
+                }
+                Status::BUFFER_TOO_SMALL => {
+                    // Allocating the buffer may add a few more descriptors.
+                    map = MemoryMap::new(len + map.descriptor_size * 4);
                 }
-                Status::BUFFER_TOO_SMALL => continue,
                 v => break Err(v),
             }
         }
     }
 
+    /// Reads the current memory map into the buffer of `map` without allocating any memory.
+    ///
+    /// This will fail with [`Status::BUFFER_TOO_SMALL`] if the buffer of `map` is not large enough,
+    /// in which case `map` will be empty.
+    pub fn read_memory_map(&self, map: &mut MemoryMap) -> Result<(), Status> {
+        let mut len = map.capacity();
+        let status = unsafe { self.read_memory_map_raw(map, &mut len) };
+
+        map.len = if status == Status::SUCCESS { len } else { 0 };
+
+        status.err_or(())
+    }
+
+    unsafe fn read_memory_map_raw(&self, map: &mut MemoryMap, len: &mut usize) -> Status {
+        (self.get_memory_map)(
+            len,
+            map.as_mut_ptr(),
+            &mut map.key,
+            &mut map.descriptor_size,
+            &mut map.descriptor_version,
+        )
+    }
+
     /// All allocations are eight-byte aligned.
     pub fn allocate_pool(&self, ty: MemoryType, size: usize) -> Result<*mut u8, Status> {
         let mut mem = null_mut();
@@ -147,8 +210,57 @@ impl BootServices {
         (self.free_pool)(mem).err_or(())
     }
 
+    /// Creates an event.
+    ///
+    /// The returned event will be closed automatically when dropped. `notify` will be dropped after
+    /// the event has been closed.
+    pub fn create_event(
+        &self,
+        ty: EventType,
+        tpl: Tpl,
+        notify: Option<EventNotify>,
+    ) -> Result<Owned<Event>, Status> {
+        let mut event = null_mut();
+
+        // Create an event without notification function.
+        let notify = match notify {
+            Some(v) => Box::into_raw(Box::new(v)),
+            None => {
+                let status = unsafe { (self.create_event)(ty, tpl, None, null_mut(), &mut event) };
+
+                if status != Status::SUCCESS {
+                    return Err(status);
+                }
+
+                return Ok(unsafe { Owned::new(event, Dtor::Function(Event::dtor)) });
+            }
+        };
+
+        // Create an event with notification function.
+        let status =
+            unsafe { (self.create_event)(ty, tpl, Some(Event::notify), notify.cast(), &mut event) };
+
+        if status != Status::SUCCESS {
+            drop(unsafe { Box::from_raw(notify) });
+            return Err(status);
+        }
+
+        // Drop the notification function after the event has been closed.
+        let dtor = Dtor::Closure(Box::new(move |e| {
+            Event::dtor(e);
+            drop(unsafe { Box::from_raw(notify) });
+        }));
+
+        Ok(unsafe { Owned::new(event, dtor) })
+    }
+
+    /// Sets the type of timer and the trigger time for a timer event. `time` is in 100ns units.
+    pub fn set_timer(&self, event: &Event, ty: TimerDelay, time: u64) -> Result<(), Status> {
+        unsafe { (self.set_timer)(event, ty, time).err_or(()) }
+    }
+
     /// Stops execution until an event is signaled.
-    pub(crate) fn wait_for_event(&self, events: &[Event]) -> Result<usize, Status> {
+    pub fn wait_for_event(&self, events: &[&Event]) -> Result<usize, Status> {
         let mut index = 0;
         let status = unsafe { (self.wait_for_event)(events.len(), events.as_ptr(), &mut index) };
 
@@ -159,6 +271,134 @@ impl BootServices {
         }
     }
 
+    /// Signals an event.
+    pub fn signal_event(&self, event: &Event) -> Result<(), Status> {
+        unsafe { (self.signal_event)(event).err_or(()) }
+    }
+
+    /// # Safety
+    /// `event` must be created by [`Self::create_event()`] and must not be used after this call.
+    pub unsafe fn close_event(&self, event: *mut Event) -> Result<(), Status> {
+        (self.close_event)(event).err_or(())
+    }
+
+    /// Checks whether an event is in the signaled state. The signaled state will be cleared if
+    /// this method returns `true`.
+    pub fn check_event(&self, event: &Event) -> Result<bool, Status> {
+        match unsafe { (self.check_event)(event) } {
+            Status::SUCCESS => Ok(true),
+            Status::NOT_READY => Ok(false),
+            v => Err(v),
+        }
+    }
+
+    /// Installs a protocol interface on `handle` or a new handle if `handle` is [`None`]. Returns
+    /// the handle that the interface was installed on.
+    ///
+    /// Use [`crate::InstalledProtocol`] instead if possible.
+    ///
+    /// # Safety
+    /// `interface` must be valid for `proto` until it is uninstalled.
+    pub unsafe fn install_protocol_interface(
+        &self,
+        handle: Option<&'static Device>,
+        proto: &Guid,
+        interface: *const (),
+    ) -> Result<&'static Device, Status> {
+        let mut handle = handle.map_or(null(), |v| v as *const Device as *const ());
+        let status =
+            (self.install_protocol_interface)(&mut handle, proto, InterfaceType::Native, interface);
+
+        if status != Status::SUCCESS {
+            Err(status)
+        } else {
+            Ok(&*(handle as *const Device))
+        }
+    }
+
+    /// Reinstalls a protocol interface on `handle`.
+    ///
+    /// # Safety
+    /// `old` must be installed on `handle` and `new` must be valid for `proto` until it is
+    /// uninstalled.
+    pub unsafe fn reinstall_protocol_interface(
+        &self,
+        handle: &Device,
+        proto: &Guid,
+        old: *const (),
+        new: *const (),
+    ) -> Result<(), Status> {
+        let handle = handle as *const Device as *const ();
+
+        (self.reinstall_protocol_interface)(handle, proto, old, new).err_or(())
+    }
+
+    /// Removes a protocol interface from `handle`.
+    ///
+    /// # Safety
+    /// `interface` must be installed on `handle`.
+    pub unsafe fn uninstall_protocol_interface(
+        &self,
+        handle: &Device,
+        proto: &Guid,
+        interface: *const (),
+    ) -> Result<(), Status> {
+        let handle = handle as *const Device as *const ();
+
+        (self.uninstall_protocol_interface)(handle, proto, interface).err_or(())
+    }
+
+    /// Creates a registration key that can be used with [`LocateSearch::ByRegisterNotify`]. `event`
+    /// will be signaled every time a protocol interface is installed for `proto`.
+    ///
+    /// Use [`crate::ProtocolNotify`] instead if possible. The registration will be removed when
+    /// `event` is closed.
+    pub fn register_protocol_notify(
+        &self,
+        proto: &Guid,
+        event: &Event,
+    ) -> Result<*const (), Status> {
+        let mut key = null();
+        let status = unsafe { (self.register_protocol_notify)(proto, event, &mut key) };
+
+        if status != Status::SUCCESS {
+            Err(status)
+        } else {
+            Ok(key)
+        }
+    }
+
+    /// Returns an array of handles that support a specified protocol.
+    pub fn locate_handle(&self, search: LocateSearch) -> Result<Vec<&'static Device>, Status> {
+        let (ty, proto, key) = search.to_raw();
+        let mut len = 1;
+
+        loop {
+            let mut size = len * size_of::<&Device>();
+            let mut buf = Vec::with_capacity(len);
+            let status = unsafe {
+                (self.locate_handle)(
+                    ty,
+                    proto,
+                    key,
+                    &mut size,
+                    buf.spare_capacity_mut().as_mut_ptr().cast(),
+                )
+            };
+
+            len = size / size_of::<&Device>();
+
+            match status {
+                Status::SUCCESS => {
+                    unsafe { buf.set_len(len) };
+                    break Ok(buf);
+                }
+                Status::BUFFER_TOO_SMALL => continue,
+                v => break Err(v),
+            }
+        }
+    }
+
     /// Locates the handle to a device on the device path that supports the specified protocol.
     pub fn locate_device_path<'a>(
         &self,
@@ -176,7 +416,84 @@ impl BootServices {
         }
     }
 
-    /// Terminates all boot services.
+    /// Adds, updates or removes a configuration table from the system table. The table will be
+    /// removed if `table` is null.
+    ///
+    /// # Safety
+    /// `table` must be valid until it is removed. Usually it should be allocated from
+    /// [`MemoryType::RuntimeServicesData`] or [`MemoryType::AcpiReclaimMemory`].
+    pub unsafe fn install_configuration_table(
+        &self,
+        guid: &Guid,
+        table: *const (),
+    ) -> Result<(), Status> {
+        (self.install_configuration_table)(guid, table).err_or(())
+    }
+
+    /// Loads an EFI image into memory from `path` or `source`. Returns the status and the handle of
+    /// the loaded image.
+    ///
+    /// Use [`Image::load_from_path()`] or [`Image::load_from_buffer()`] instead if possible. The
+    /// handle will also be returned with [`Status::SECURITY_VIOLATION`] if the image was loaded but
+    /// failed the security check, in which case the image cannot be started and must be unloaded
+    /// with [`Self::unload_image()`].
+    pub fn load_image(
+        &self,
+        boot_policy: bool,
+        parent: &Image,
+        path: Option<&Path>,
+        source: Option<&[u8]>,
+    ) -> (Status, Option<&'static Image>) {
+        let path = path.map_or(null(), |v| v.as_bytes().as_ptr());
+        let (src, len) = source.map_or((null(), 0), |v| (v.as_ptr(), v.len()));
+        let mut image = null();
+        let status = unsafe { (self.load_image)(boot_policy, parent, path, src, len, &mut image) };
+        let image = match status {
+            Status::SUCCESS | Status::SECURITY_VIOLATION => unsafe { image.as_ref() },
+            _ => None,
+        };
+
+        (status, image)
+    }
+
+    /// Transfers control to a loaded image's entry point. Returns the exit code and exit data of
+    /// the image.
+    ///
+    /// # Safety
+    /// `image` must be loaded by [`Self::load_image()`] and not started yet.
+    pub unsafe fn start_image(&self, image: &Image) -> (Status, Option<ExitData>) {
+        let mut len = 0;
+        let mut data = null_mut();
+        let status = (self.start_image)(image, &mut len, &mut data);
+        let data = if data.is_null() {
+            None
+        } else {
+            Some(ExitData::new(data, len))
+        };
+
+        (status, data)
+    }
+
+    /// Terminates the image that is currently running or unloads the image that is not started.
+    ///
+    /// # Safety
+    /// `data` must be either null or allocated with [`Self::allocate_pool()`]. `len` is in bytes.
+    /// This method will not return if `image` is the image that currently running, which mean any
+    /// values on the stack will not be dropped.
+    pub unsafe fn exit(&self, image: &Image, status: Status, data: *mut u16, len: usize) -> Status {
+        (self.exit)(image, status, len, data)
+    }
+
+    /// Unloads an image.
+    ///
+    /// # Safety
+    /// `image` must be loaded by [`Self::load_image()`] and must not be used after this call.
+    pub unsafe fn unload_image(&self, image: &Image) -> Result<(), Status> {
+        (self.unload_image)(image).err_or(())
+    }
+
+    /// Terminates all boot services. Use [`crate::exit_boot_services()`] instead if you need ZFI to
+    /// remain usable after this call.
     ///
     /// # Safety
     /// Once this method is returned any functions provided by ZFI will not be usable. Beware of any
@@ -192,6 +509,80 @@ impl BootServices {
         }
     }
 
+    /// Returns a monotonically increasing count for the platform.
+    pub fn get_next_monotonic_count(&self) -> Result<u64, Status> {
+        let mut count = 0;
+        let status = unsafe { (self.get_next_monotonic_count)(&mut count) };
+
+        if status != Status::SUCCESS {
+            Err(status)
+        } else {
+            Ok(count)
+        }
+    }
+
+    /// Stalls the processor for at least `us` microseconds.
+    pub fn stall(&self, us: usize) -> Result<(), Status> {
+        (self.stall)(us).err_or(())
+    }
+
+    /// Sets the system's watchdog timer. `timeout` is in seconds and zero will disable the timer.
+    ///
+    /// `code` from `0x0000` to `0xFFFF` are reserved for the firmware.
+    pub fn set_watchdog_timer(
+        &self,
+        timeout: usize,
+        code: u64,
+        data: Option<&EfiStr>,
+    ) -> Result<(), Status> {
+        let (len, data) = data.map_or((0, null()), |v| (v.len() * 2 + 2, v.as_ptr()));
+
+        unsafe { (self.set_watchdog_timer)(timeout, code, len, data).err_or(()) }
+    }
+
+    /// Connects one or more drivers to `controller`.
+    ///
+    /// If `drivers` is [`None`] the firmware will select the drivers itself. `remaining` is the
+    /// remaining device path of the child controller to create, which is only a hint for the
+    /// drivers.
+    pub fn connect_controller(
+        &self,
+        controller: &Device,
+        drivers: Option<&[&Image]>,
+        remaining: Option<&Path>,
+        recursive: bool,
+    ) -> Result<(), Status> {
+        // Build a NULL-terminated list of driver handles.
+        let drivers: Option<Vec<*const Image>> = drivers.map(|v| {
+            v.iter()
+                .map(|&v| v as *const Image)
+                .chain([null()])
+                .collect()
+        });
+
+        // Connect.
+        let drivers = drivers.as_ref().map_or(null(), |v| v.as_ptr());
+        let remaining = remaining.map_or(null(), |v| v.as_bytes().as_ptr());
+
+        unsafe { (self.connect_controller)(controller, drivers, remaining, recursive).err_or(()) }
+    }
+
+    /// Disconnects one or more drivers from `controller`.
+    ///
+    /// If `driver` is [`None`] all drivers that are managing `controller` will be disconnected. If
+    /// `child` is [`None`] all child controllers of `controller` will be destroyed.
+    pub fn disconnect_controller(
+        &self,
+        controller: &Device,
+        driver: Option<&Image>,
+        child: Option<&Device>,
+    ) -> Result<(), Status> {
+        let driver = driver.map_or(null(), |v| v as *const Image);
+        let child = child.map_or(null(), |v| v as *const Device);
+
+        unsafe { (self.disconnect_controller)(controller, driver, child).err_or(()) }
+    }
+
     /// # Safety
     /// This method don't check anything so the caller is responsible to make sure all arguments is
     /// valid for `EFI_BOOT_SERVICES.OpenProtocol()`.
@@ -199,13 +590,7 @@ impl BootServices {
         let agent = IMAGE.cast();
         let attrs = OpenProtocolAttributes::GET_PROTOCOL;
 
-        match self.open_protocol(handle, proto, agent, null(), attrs) {
-            Ok(v) => Some(v),
-            Err(e) => {
-                assert_eq!(e, Status::UNSUPPORTED);
-                None
-            }
-        }
+        self.open_protocol(handle, proto, agent, null(), attrs).ok()
     }
 
     /// # Safety
@@ -228,6 +613,185 @@ impl BootServices {
             Ok(interface)
         }
     }
+
+    /// Closes a protocol on a handle that was opened using [`Self::open_protocol()`].
+    ///
+    /// # Safety
+    /// The interface of the protocol must not be used after this call.
+    pub unsafe fn close_protocol(
+        &self,
+        handle: &Device,
+        proto: &Guid,
+        agent: &Image,
+        controller: Option<&Device>,
+    ) -> Result<(), Status> {
+        let handle = handle as *const Device as *const ();
+        let agent = agent as *const Image as *const ();
+        let controller = controller.map_or(null(), |v| v as *const Device as *const ());
+
+        (self.close_protocol)(handle, proto, agent, controller).err_or(())
+    }
+
+    /// Retrieves the list of protocol interface GUIDs that are installed on a handle.
+    pub fn protocols_per_handle(&self, handle: &Device) -> Result<Vec<Guid>, Status> {
+        let handle = handle as *const Device as *const ();
+        let mut buf = null_mut();
+        let mut len = 0;
+        let status = unsafe { (self.protocols_per_handle)(handle, &mut buf, &mut len) };
+
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+
+        // Copy the GUIDs so the buffer can be freed.
+        let guids = unsafe { core::slice::from_raw_parts(buf, len) }
+            .iter()
+            .map(|&v| unsafe { *v })
+            .collect();
+
+        let _ = unsafe { self.free_pool(buf.cast()) };
+
+        Ok(guids)
+    }
+
+    /// Returns an array of handles that support the requested protocol in a buffer allocated from
+    /// pool.
+    ///
+    /// The returned array is a copy of the buffer, which was already freed.
+    pub fn locate_handle_buffer(
+        &self,
+        search: LocateSearch,
+    ) -> Result<Vec<&'static Device>, Status> {
+        let (ty, proto, key) = search.to_raw();
+        let mut len = 0;
+        let mut buf = null_mut();
+        let status = unsafe { (self.locate_handle_buffer)(ty, proto, key, &mut len, &mut buf) };
+
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+
+        // Copy the handles so the buffer can be freed.
+        let handles = unsafe { core::slice::from_raw_parts(buf, len) }.to_vec();
+
+        let _ = unsafe { self.free_pool(buf.cast()) };
+
+        Ok(handles)
+    }
+
+    /// Returns the first protocol interface that matches `proto` or [`None`] if no such interface.
+    pub fn locate_protocol(&self, proto: &Guid) -> Option<*const ()> {
+        let mut interface = null();
+        let status = unsafe { (self.locate_protocol)(proto, null(), &mut interface) };
+
+        if status != Status::SUCCESS {
+            None
+        } else {
+            Some(interface)
+        }
+    }
+
+    /// Installs one or more protocol interfaces on `handle` or a new handle if `handle` is [`None`].
+    /// Returns the handle that the interfaces was installed on.
+    ///
+    /// Either all interfaces are installed or none of them are installed. This method supports up
+    /// to 4 interfaces and will return [`Status::INVALID_PARAMETER`] if `interfaces` is empty or
+    /// contains more than that.
+    ///
+    /// # Safety
+    /// Each interface must be valid for the GUID it was paired with until it is uninstalled.
+    pub unsafe fn install_multiple_protocol_interfaces(
+        &self,
+        handle: Option<&'static Device>,
+        interfaces: &[(&Guid, *const ())],
+    ) -> Result<&'static Device, Status> {
+        let mut handle = handle.map_or(null(), |v| v as *const Device as *const ());
+        let f = self.install_multiple_protocol_interfaces;
+        let e = null::<()>();
+        let status = match *interfaces {
+            [(g1, i1)] => f(&mut handle, g1, i1, e),
+            [(g1, i1), (g2, i2)] => f(&mut handle, g1, i1, g2, i2, e),
+            [(g1, i1), (g2, i2), (g3, i3)] => f(&mut handle, g1, i1, g2, i2, g3, i3, e),
+            [(g1, i1), (g2, i2), (g3, i3), (g4, i4)] => {
+                f(&mut handle, g1, i1, g2, i2, g3, i3, g4, i4, e)
+            }
+            _ => return Err(Status::INVALID_PARAMETER),
+        };
+
+        if status != Status::SUCCESS {
+            Err(status)
+        } else {
+            Ok(&*(handle as *const Device))
+        }
+    }
+
+    /// Removes one or more protocol interfaces from `handle`.
+    ///
+    /// Either all interfaces are removed or none of them are removed. This method has the same
+    /// limitation as [`Self::install_multiple_protocol_interfaces()`].
+    ///
+    /// # Safety
+    /// Each interface must be installed on `handle`.
+    pub unsafe fn uninstall_multiple_protocol_interfaces(
+        &self,
+        handle: &Device,
+        interfaces: &[(&Guid, *const ())],
+    ) -> Result<(), Status> {
+        let handle = handle as *const Device as *const ();
+        let f = self.uninstall_multiple_protocol_interfaces;
+        let e = null::<()>();
+        let status = match *interfaces {
+            [(g1, i1)] => f(handle, g1, i1, e),
+            [(g1, i1), (g2, i2)] => f(handle, g1, i1, g2, i2, e),
+            [(g1, i1), (g2, i2), (g3, i3)] => f(handle, g1, i1, g2, i2, g3, i3, e),
+            [(g1, i1), (g2, i2), (g3, i3), (g4, i4)] => {
+                f(handle, g1, i1, g2, i2, g3, i3, g4, i4, e)
+            }
+            _ => return Err(Status::INVALID_PARAMETER),
+        };
+
+        status.err_or(())
+    }
+}
+
+/// Search criteria of [`BootServices::locate_handle()`] and
+/// [`BootServices::locate_handle_buffer()`].
+#[derive(Clone, Copy)]
+pub enum LocateSearch<'a> {
+    /// All handles in the system.
+    AllHandles,
+
+    /// The next handle that is new for the registration. Only one handle is returned at a time.
+    ByRegisterNotify(&'a ProtocolNotify),
+
+    /// All handles that support the specified protocol.
+    ByProtocol(&'a Guid),
+}
+
+impl LocateSearch<'_> {
+    fn to_raw(self) -> (LocateSearchType, *const Guid, *const ()) {
+        match self {
+            Self::AllHandles => (LocateSearchType::AllHandles, null(), null()),
+            Self::ByRegisterNotify(v) => (LocateSearchType::ByRegisterNotify, null(), v.key()),
+            Self::ByProtocol(v) => (LocateSearchType::ByProtocol, v, null()),
+        }
+    }
+}
+
+/// Represents an `EFI_INTERFACE_TYPE`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+enum InterfaceType {
+    Native,
+}
+
+/// Represents an `EFI_LOCATE_SEARCH_TYPE`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+enum LocateSearchType {
+    AllHandles,
+    ByRegisterNotify,
+    ByProtocol,
 }
 
 /// Represents an `EFI_ALLOCATE_TYPE`.
@@ -238,8 +802,23 @@ pub enum AllocateType {
     Address,
 }
 
+/// Represents an `EFI_TPL`.
+///
+/// The firmware may use any level between the predefined levels (e.g. `TPL_CALLBACK + 1`).
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Tpl(pub(crate) usize);
+
+impl Tpl {
+    pub const APPLICATION: Self = Self(4);
+    pub const CALLBACK: Self = Self(8);
+    pub const NOTIFY: Self = Self(16);
+    pub const HIGH_LEVEL: Self = Self(31);
+}
+
 /// Represents an `EFI_MEMORY_TYPE`.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MemoryType {
     /// Not usable.
     Reserved,
@@ -265,20 +844,143 @@ pub enum MemoryType {
     Unaccepted,
 }
 
+impl MemoryType {
+    /// Returns [`None`] if `v` is not a memory type defined by the UEFI specifications (e.g. OEM or
+    /// OS defined).
+    pub const fn from_raw(v: u32) -> Option<Self> {
+        let v = match v {
+            0 => Self::Reserved,
+            1 => Self::LoaderCode,
+            2 => Self::LoaderData,
+            3 => Self::BootServicesCode,
+            4 => Self::BootServicesData,
+            5 => Self::RuntimeServicesCode,
+            6 => Self::RuntimeServicesData,
+            7 => Self::ConventionalMemory,
+            8 => Self::UnusableMemory,
+            9 => Self::AcpiReclaimMemory,
+            10 => Self::AcpiMemoryNvs,
+            11 => Self::MemoryMappedIo,
+            12 => Self::MemoryMappedIoPortSpace,
+            13 => Self::PalCode,
+            14 => Self::PersistentMemory,
+            15 => Self::Unaccepted,
+            _ => return None,
+        };
+
+        Some(v)
+    }
+}
+
 /// Represents an `EFI_MEMORY_DESCRIPTOR`.
 #[repr(C)]
+#[derive(Debug, Clone, Copy)]
 pub struct MemoryDescriptor {
-    ty: u32,
-    physical_start: u64,
-    virtual_start: u64,
-    number_of_pages: u64,
-    attribute: u64,
+    pub(crate) ty: u32,
+    pub(crate) physical_start: u64,
+    pub(crate) virtual_start: u64,
+    pub(crate) number_of_pages: u64,
+    pub(crate) attribute: u64,
+}
+
+impl MemoryDescriptor {
+    /// Returns [`None`] if the type is not defined by the UEFI specifications. Use
+    /// [`Self::raw_type()`] to get the value in this case.
+    pub fn ty(&self) -> Option<MemoryType> {
+        MemoryType::from_raw(self.ty)
+    }
+
+    pub fn raw_type(&self) -> u32 {
+        self.ty
+    }
+
+    pub fn physical_start(&self) -> u64 {
+        self.physical_start
+    }
+
+    pub fn virtual_start(&self) -> u64 {
+        self.virtual_start
+    }
+
+    /// Returns number of 4 KiB pages in this region.
+    pub fn page_count(&self) -> u64 {
+        self.number_of_pages
+    }
+
+    /// Returns the size of this region in bytes.
+    pub fn len(&self) -> u64 {
+        self.number_of_pages * PAGE_SIZE as u64
+    }
+
+    /// Returns `true` if this region has no pages.
+    pub fn is_empty(&self) -> bool {
+        self.number_of_pages == 0
+    }
+
+    pub fn attributes(&self) -> MemoryAttributes {
+        MemoryAttributes::from_bits_retain(self.attribute)
+    }
+
+    /// Returns how this region can be used after `EFI_BOOT_SERVICES.ExitBootServices()`.
+    pub fn region_kind(&self) -> RegionKind {
+        match self.ty() {
+            Some(
+                MemoryType::LoaderCode
+                | MemoryType::LoaderData
+                | MemoryType::BootServicesCode
+                | MemoryType::BootServicesData
+                | MemoryType::ConventionalMemory,
+            ) => RegionKind::Usable,
+            Some(MemoryType::AcpiReclaimMemory) => RegionKind::AcpiReclaimable,
+            Some(MemoryType::AcpiMemoryNvs) => RegionKind::AcpiNvs,
+            Some(MemoryType::UnusableMemory) => RegionKind::Unusable,
+            Some(MemoryType::PersistentMemory) => RegionKind::Persistent,
+            _ => RegionKind::Reserved,
+        }
+    }
+
+    /// Returns `true` if the OS can use this region after `EFI_BOOT_SERVICES.ExitBootServices()`.
+    ///
+    /// Keep in mind that [`MemoryType::LoaderCode`] and [`MemoryType::LoaderData`] may still
+    /// contains the data that was prepared by the loader (e.g. the kernel image).
+    pub fn is_usable_after_exit(&self) -> bool {
+        self.region_kind() == RegionKind::Usable
+    }
+}
+
+bitflags! {
+    /// Attributes of [`MemoryDescriptor`].
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MemoryAttributes: u64 {
+        const UC = 0x0000000000000001;
+        const WC = 0x0000000000000002;
+        const WT = 0x0000000000000004;
+        const WB = 0x0000000000000008;
+        const UCE = 0x0000000000000010;
+        const WP = 0x0000000000001000;
+        const RP = 0x0000000000002000;
+        const XP = 0x0000000000004000;
+        const NV = 0x0000000000008000;
+        const MORE_RELIABLE = 0x0000000000010000;
+        const RO = 0x0000000000020000;
+        const SP = 0x0000000000040000;
+        const CPU_CRYPTO = 0x0000000000080000;
+        const HOT_PLUGGABLE = 0x0000000000100000;
+        const ISA_VALID = 0x4000000000000000;
+        const RUNTIME = 0x8000000000000000;
+    }
 }
 
 bitflags! {
     /// Attributes of [`BootServices::open_protocol()`].
     #[repr(transparent)]
     pub struct OpenProtocolAttributes: u32 {
+        const BY_HANDLE_PROTOCOL = 0x00000001;
         const GET_PROTOCOL = 0x00000002;
+        const TEST_PROTOCOL = 0x00000004;
+        const BY_CHILD_CONTROLLER = 0x00000008;
+        const BY_DRIVER = 0x00000010;
+        const EXCLUSIVE = 0x00000020;
     }
 }
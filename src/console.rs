@@ -1,31 +1,38 @@
-use crate::event::Event;
-use crate::{system_table, EfiStr, Status};
+use crate::{system_table, EfiStr, Event, ReadKey, Status};
 use alloc::vec::Vec;
 use core::fmt::Write;
 
 /// Prints to the standard output, with a newline.
+///
+/// This macro will do nothing if [`crate::exit_boot_services()`] has been called.
 #[macro_export]
 macro_rules! println {
     ($($args:tt)*) => {{
         use ::core::fmt::Write;
 
-        let mut dev = $crate::system_table().stdout();
+        if !$crate::boot_services_exited() {
+            let mut dev = $crate::system_table().stdout();
 
-        dev.write_fmt(::core::format_args!($($args)*)).unwrap();
-        dev.write_eol().unwrap();
+            dev.write_fmt(::core::format_args!($($args)*)).unwrap();
+            dev.write_eol().unwrap();
+        }
     }};
 }
 
 /// Prints to the standard error, with a newline.
+///
+/// This macro will do nothing if [`crate::exit_boot_services()`] has been called.
 #[macro_export]
 macro_rules! eprintln {
     ($($args:tt)*) => {{
         use ::core::fmt::Write;
 
-        let mut dev = $crate::system_table().stderr();
+        if !$crate::boot_services_exited() {
+            let mut dev = $crate::system_table().stderr();
 
-        dev.write_fmt(::core::format_args!($($args)*)).unwrap();
-        dev.write_eol().unwrap();
+            dev.write_fmt(::core::format_args!($($args)*)).unwrap();
+            dev.write_eol().unwrap();
+        }
     }};
 }
 
@@ -35,7 +42,7 @@ pub fn pause() {
 
     system_table()
         .boot_services()
-        .wait_for_event(&[stdin.wait_for_key])
+        .wait_for_event(&[stdin.wait_for_key()])
         .unwrap();
 }
 
@@ -43,8 +50,62 @@ pub fn pause() {
 #[repr(C)]
 pub struct SimpleTextInput {
     reset: fn(),
-    read_key_stroke: fn(),
-    wait_for_key: Event,
+    read_key_stroke: unsafe extern "efiapi" fn(&Self, *mut InputKey) -> Status,
+    wait_for_key: *const Event,
+}
+
+impl SimpleTextInput {
+    /// Reads the next keystroke from the input device. Returns [`None`] if no keystroke is
+    /// available.
+    pub fn read_key_stroke(&self) -> Result<Option<InputKey>, Status> {
+        let mut key = InputKey {
+            scan_code: 0,
+            unicode_char: 0,
+        };
+
+        match unsafe { (self.read_key_stroke)(self, &mut key) } {
+            Status::SUCCESS => Ok(Some(key)),
+            Status::NOT_READY => Ok(None),
+            v => Err(v),
+        }
+    }
+
+    /// Returns a future that will be resolved with the next keystroke.
+    ///
+    /// The returned future must be polled by [`crate::block_on()`].
+    pub fn read_key(&self) -> ReadKey<'_> {
+        ReadKey(self)
+    }
+
+    /// Gets an event to use with [`super::BootServices::wait_for_event()`] to wait for a key to be
+    /// available.
+    pub fn wait_for_key(&self) -> &Event {
+        unsafe { &*self.wait_for_key }
+    }
+}
+
+/// Represents an `EFI_INPUT_KEY`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputKey {
+    scan_code: u16,
+    unicode_char: u16,
+}
+
+impl InputKey {
+    /// Returns the scan code of the key or zero if the key is a Unicode character.
+    pub fn scan_code(&self) -> u16 {
+        self.scan_code
+    }
+
+    /// Returns the Unicode character of the key or [`None`] if the key is not a printable
+    /// character.
+    pub fn unicode_char(&self) -> Option<char> {
+        match self.unicode_char {
+            0 => None,
+            v => char::from_u32(v.into()),
+        }
+    }
 }
 
 /// Represents an `EFI_SIMPLE_TEXT_OUTPUT_PROTOCOL`.
@@ -75,6 +136,7 @@ impl Write for &SimpleTextOutput {
         let mut prev = 0;
 
         for c in s.encode_utf16() {
+            #[allow(clippy::collapsible_match)]
             match c {
                 0x0000 | 0xD800..=0xDFFF => return Err(core::fmt::Error),
                 0x000A => {
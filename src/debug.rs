@@ -10,8 +10,8 @@ use thiserror::Error;
 
 /// Prints to the debug log, with a newline.
 ///
-/// This macro will do nothing if no debug writer specified during ZFI initialization. See
-/// [`debug_writer()`] for more information.
+/// This macro will do nothing if no debug writer specified during ZFI initialization or
+/// [`crate::exit_boot_services()`] has been called. See [`debug_writer()`] for more information.
 #[macro_export]
 macro_rules! debugln {
     ($($args:tt)*) => {
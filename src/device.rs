@@ -1,32 +1,159 @@
-use crate::{get_protocol, system_table, Guid, Path, SimpleFileSystem, Status};
+use crate::{
+    current_image, system_table, Guid, Image, LocateSearch, OpenProtocolAttributes, OpenedProtocol,
+    Path, Protocol, SimpleFileSystem, Status,
+};
+use alloc::vec::Vec;
+use core::ptr::null;
 
 /// Represents an `EFI_HANDLE` for a device.
 pub struct Device(());
 
 impl Device {
+    /// Connects all drivers that can manage all handles in the system, which is the same as
+    /// `connect -r` in the UEFI Shell.
+    ///
+    /// Usually you need to call this when the firmware has fast boot enabled, which will not
+    /// connect the devices that are not required to boot. Any errors on individual handle will be
+    /// ignored.
+    pub fn connect_all() -> Result<(), Status> {
+        for h in Self::all()? {
+            let _ = h.connect(None, None, true);
+        }
+
+        Ok(())
+    }
+
     pub fn locate<'a>(proto: &Guid, path: &'a Path) -> Result<(&'static Self, &'a Path), Status> {
         system_table()
             .boot_services()
             .locate_device_path(proto, path)
     }
 
-    pub fn path(&self) -> Option<&Path> {
-        static ID: Guid = Guid::new(
-            0x09576e91,
-            0x6d3f,
-            0x11d2,
-            [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
-        );
-
-        unsafe {
-            get_protocol(self as *const Device as *const (), &ID).map(|v| Path::from_ptr(v as _))
+    /// Returns all handles in the system.
+    pub fn all() -> Result<Vec<&'static Self>, Status> {
+        system_table()
+            .boot_services()
+            .locate_handle_buffer(LocateSearch::AllHandles)
+    }
+
+    /// Returns all handles that support `P`. The result will be empty if there are no any handles
+    /// supports `P`.
+    pub fn find<P: Protocol>() -> Result<Vec<&'static Self>, Status> {
+        Self::find_by_guid(&P::ID)
+    }
+
+    /// Returns all handles that support `proto`. The result will be empty if there are no any
+    /// handles supports `proto`.
+    pub fn find_by_guid(proto: &Guid) -> Result<Vec<&'static Self>, Status> {
+        match system_table()
+            .boot_services()
+            .locate_handle_buffer(LocateSearch::ByProtocol(proto))
+        {
+            Ok(v) => Ok(v),
+            Err(Status::NOT_FOUND) => Ok(Vec::new()),
+            Err(e) => Err(e),
         }
     }
 
+    /// Returns GUIDs of the protocols that are installed on this handle.
+    pub fn protocols(&self) -> Result<Vec<Guid>, Status> {
+        system_table().boot_services().protocols_per_handle(self)
+    }
+
+    /// Gets the device path of this handle. Returns [`None`] if this handle does not have a device
+    /// path.
+    ///
+    /// This uses the same lookup as [`Self::get()`] so the returned path is not tracked by the
+    /// firmware.
+    pub fn path(&self) -> Option<&Path> {
+        self.interface(&Path::ID)
+            .map(|v| unsafe { Path::from_ptr(v.cast()) })
+    }
+
     pub fn file_system(&self) -> Option<&SimpleFileSystem> {
-        unsafe {
-            get_protocol(self as *const Device as *const (), &SimpleFileSystem::ID)
-                .map(|v| &*(v as *const SimpleFileSystem))
-        }
+        self.get()
+    }
+
+    /// Gets the interface of `P` from this handle. Returns [`None`] if this handle does not support
+    /// `P`.
+    ///
+    /// The returned interface is not tracked by the firmware, which is the same as
+    /// `EFI_BOOT_SERVICES.HandleProtocol()`. Use [`Self::open()`] if you want the firmware to track
+    /// it.
+    pub fn get<P: Protocol>(&self) -> Option<&P> {
+        self.interface(&P::ID).map(|v| unsafe { &*(v as *const P) })
+    }
+
+    /// Opens `P` on this handle with the current image as an agent and this handle as a controller.
+    ///
+    /// The protocol will be closed when the returned [`OpenedProtocol`] is dropped. `attrs` cannot
+    /// contains [`OpenProtocolAttributes::TEST_PROTOCOL`]. Use [`Self::supports()`] instead.
+    pub fn open<P: Protocol>(
+        &self,
+        attrs: OpenProtocolAttributes,
+    ) -> Result<OpenedProtocol<'_, P>, Status> {
+        OpenedProtocol::new(self, current_image(), Some(self), attrs)
+    }
+
+    /// Opens `P` on this handle with a custom agent and controller.
+    ///
+    /// See [`Self::open()`] for more details.
+    pub fn open_with<'a, P: Protocol>(
+        &'a self,
+        agent: &'a Image,
+        controller: Option<&'a Device>,
+        attrs: OpenProtocolAttributes,
+    ) -> Result<OpenedProtocol<'a, P>, Status> {
+        OpenedProtocol::new(self, agent, controller, attrs)
+    }
+
+    /// Connects one or more drivers to this controller. See
+    /// [`crate::BootServices::connect_controller()`] for more details.
+    pub fn connect(
+        &self,
+        drivers: Option<&[&Image]>,
+        remaining: Option<&Path>,
+        recursive: bool,
+    ) -> Result<(), Status> {
+        system_table()
+            .boot_services()
+            .connect_controller(self, drivers, remaining, recursive)
+    }
+
+    /// Disconnects one or more drivers from this controller. See
+    /// [`crate::BootServices::disconnect_controller()`] for more details.
+    pub fn disconnect(&self, driver: Option<&Image>, child: Option<&Device>) -> Result<(), Status> {
+        system_table()
+            .boot_services()
+            .disconnect_controller(self, driver, child)
+    }
+
+    /// Returns `true` if this handle supports `P`.
+    pub fn supports<P: Protocol>(&self) -> bool {
+        let status = unsafe {
+            system_table().boot_services().open_protocol(
+                self as *const Device as *const (),
+                &P::ID,
+                current_image() as *const Image as *const (),
+                self as *const Device as *const (),
+                OpenProtocolAttributes::TEST_PROTOCOL,
+            )
+        };
+
+        status.is_ok()
+    }
+
+    fn interface(&self, proto: &Guid) -> Option<*const ()> {
+        let proto = unsafe {
+            system_table().boot_services().open_protocol(
+                self as *const Device as *const (),
+                proto,
+                current_image() as *const Image as *const (),
+                null(),
+                OpenProtocolAttributes::GET_PROTOCOL,
+            )
+        };
+
+        proto.ok()
     }
 }
@@ -0,0 +1,321 @@
+use crate::{
+    current_image, exit, fini, system_table, Device, EfiStr, Guid, Image, Path, Protocol, Status,
+};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::CStr;
+use core::ptr::null_mut;
+use core::slice::from_raw_parts;
+
+static mut DRIVER: *mut Driver = null_mut();
+
+/// Installs `binding` as the driver for the current image.
+///
+/// This will install `EFI_DRIVER_BINDING_PROTOCOL` on the image handle and also
+/// `EFI_COMPONENT_NAME2_PROTOCOL` if [`DriverBinding::driver_name()`] returns [`Some`]. The
+/// `Unload()` of the image will also be set so the driver can be unloaded, which will disconnect
+/// the driver from all controllers and drop `binding`.
+///
+/// Usually you don't need to call this function directly. Use `zfi::driver` macro instead.
+pub fn install_driver(binding: Box<dyn DriverBinding>) -> Result<(), Status> {
+    let image = current_image();
+
+    if unsafe { !DRIVER.is_null() } {
+        return Err(Status::ALREADY_STARTED);
+    }
+
+    // Setup the driver.
+    let driver = Box::into_raw(Box::new(Driver {
+        binding: DriverBindingProtocol {
+            supported: DriverBindingProtocol::supported,
+            start: DriverBindingProtocol::start,
+            stop: DriverBindingProtocol::stop,
+            version: binding.version(),
+            image,
+            handle: image,
+        },
+        name: ComponentName2 {
+            get_driver_name: ComponentName2::get_driver_name,
+            get_controller_name: ComponentName2::get_controller_name,
+            languages: LANGUAGE.as_ptr().cast(),
+        },
+        named: binding.driver_name().is_some(),
+        imp: binding,
+    }));
+
+    // Install the protocols. The firmware may invoke the driver before the installation was
+    // completed so we need to set DRIVER first.
+    let handle = unsafe { &*(image as *const Image as *const Device) };
+    let result = unsafe {
+        DRIVER = driver;
+
+        system_table()
+            .boot_services()
+            .install_multiple_protocol_interfaces(Some(handle), &(*driver).interfaces())
+    };
+
+    if let Err(e) = result {
+        unsafe { DRIVER = null_mut() };
+        drop(unsafe { Box::from_raw(driver) });
+        return Err(e);
+    }
+
+    // Enable unloading.
+    unsafe { image.set_unload(unload) };
+
+    Ok(())
+}
+
+/// Terminates the driver with [`Status::ABORTED`].
+///
+/// If the driver has not been installed yet (e.g. the entry point is still running) this will exit
+/// the image. Otherwise this will never return since the driver cannot exit once its entry point
+/// has been returned (e.g. panic inside [`DriverBinding::start()`]). Use [`crate::reset()`] instead
+/// if you want to reboot the system.
+///
+/// Usually you don't need to call this function directly. The panic handler that was generated by
+/// `zfi::driver` macro will call this function unless `reboot_on_panic` is specified.
+pub fn abort_driver() -> ! {
+    if unsafe { DRIVER.is_null() } {
+        exit(Status::ABORTED, None);
+    }
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Implementation of `EFI_LOADED_IMAGE_PROTOCOL.Unload()` for a driver.
+unsafe extern "efiapi" fn unload(image: &Image) -> Status {
+    let driver = DRIVER;
+
+    // Stop the driver on all controllers that it is managing.
+    if let Ok(handles) = Device::all() {
+        for h in handles {
+            let _ = h.disconnect(Some(image), None);
+        }
+    }
+
+    // Uninstall the protocols.
+    let handle = &*(image as *const Image as *const Device);
+    let result = system_table()
+        .boot_services()
+        .uninstall_multiple_protocol_interfaces(handle, &(*driver).interfaces());
+
+    if let Err(e) = result {
+        return e;
+    }
+
+    // Drop the driver and our states.
+    DRIVER = null_mut();
+
+    drop(Box::from_raw(driver));
+    fini();
+
+    Status::SUCCESS
+}
+
+/// A driver that follows the UEFI Driver Model.
+///
+/// The implementation will be dropped when the driver is unloaded.
+pub trait DriverBinding {
+    /// Tests to see if this driver supports `controller`. `remaining` is the remaining device path
+    /// of the child controller to create, if any.
+    fn supported(&self, controller: &Device, remaining: Option<&Path>) -> Result<(), Status>;
+
+    /// Starts this driver on `controller`.
+    fn start(&self, controller: &Device, remaining: Option<&Path>) -> Result<(), Status>;
+
+    /// Stops this driver on `controller`. If `children` is empty the driver must stop managing
+    /// `controller` otherwise it must destroy only `children`.
+    fn stop(&self, controller: &Device, children: &[&Device]) -> Result<(), Status>;
+
+    /// Returns the version number of this driver.
+    fn version(&self) -> u32 {
+        0x10
+    }
+
+    /// Returns the English name of this driver. `EFI_COMPONENT_NAME2_PROTOCOL` will be installed
+    /// only if this method return [`Some`].
+    fn driver_name(&self) -> Option<&EfiStr> {
+        None
+    }
+
+    /// Returns the English name of `controller` or `child` that is being managed by this driver.
+    fn controller_name(&self, controller: &Device, child: Option<&Device>) -> Option<&EfiStr> {
+        let _ = controller;
+        let _ = child;
+        None
+    }
+}
+
+/// Language code for `EFI_COMPONENT_NAME2_PROTOCOL`.
+const LANGUAGE: &CStr = c"en";
+
+/// States of the driver.
+struct Driver {
+    binding: DriverBindingProtocol,
+    name: ComponentName2,
+    named: bool,
+    imp: Box<dyn DriverBinding>,
+}
+
+impl Driver {
+    fn interfaces(&self) -> Vec<(&Guid, *const ())> {
+        let mut list = vec![(
+            &DriverBindingProtocol::ID,
+            &self.binding as *const DriverBindingProtocol as *const (),
+        )];
+
+        if self.named {
+            list.push((
+                &ComponentName2::ID,
+                &self.name as *const ComponentName2 as *const (),
+            ));
+        }
+
+        list
+    }
+
+    unsafe fn get<'a>() -> &'a Self {
+        &*DRIVER
+    }
+}
+
+/// Represents an `EFI_DRIVER_BINDING_PROTOCOL`.
+#[repr(C)]
+struct DriverBindingProtocol {
+    supported: unsafe extern "efiapi" fn(&Self, &Device, *const u8) -> Status,
+    start: unsafe extern "efiapi" fn(&Self, &Device, *const u8) -> Status,
+    stop: unsafe extern "efiapi" fn(&Self, &Device, usize, *const &Device) -> Status,
+    version: u32,
+    image: &'static Image,
+    handle: &'static Image,
+}
+
+impl DriverBindingProtocol {
+    unsafe extern "efiapi" fn supported(
+        &self,
+        controller: &Device,
+        remaining: *const u8,
+    ) -> Status {
+        let remaining = match remaining.is_null() {
+            true => None,
+            false => Some(Path::from_ptr(remaining)),
+        };
+
+        match Driver::get().imp.supported(controller, remaining) {
+            Ok(_) => Status::SUCCESS,
+            Err(e) => e,
+        }
+    }
+
+    unsafe extern "efiapi" fn start(&self, controller: &Device, remaining: *const u8) -> Status {
+        let remaining = match remaining.is_null() {
+            true => None,
+            false => Some(Path::from_ptr(remaining)),
+        };
+
+        match Driver::get().imp.start(controller, remaining) {
+            Ok(_) => Status::SUCCESS,
+            Err(e) => e,
+        }
+    }
+
+    unsafe extern "efiapi" fn stop(
+        &self,
+        controller: &Device,
+        len: usize,
+        children: *const &Device,
+    ) -> Status {
+        let children = match len {
+            0 => &[],
+            n => from_raw_parts(children, n),
+        };
+
+        match Driver::get().imp.stop(controller, children) {
+            Ok(_) => Status::SUCCESS,
+            Err(e) => e,
+        }
+    }
+}
+
+unsafe impl Protocol for DriverBindingProtocol {
+    const ID: Guid = Guid::new(
+        0x18a031ab,
+        0xb443,
+        0x4d1a,
+        [0xa5, 0xc0, 0x0c, 0x09, 0x26, 0x1e, 0x9f, 0x71],
+    );
+}
+
+/// Represents an `EFI_COMPONENT_NAME2_PROTOCOL`.
+#[repr(C)]
+struct ComponentName2 {
+    get_driver_name: unsafe extern "efiapi" fn(&Self, *const u8, *mut *const u16) -> Status,
+    get_controller_name: unsafe extern "efiapi" fn(
+        &Self,
+        &Device,
+        *const Device,
+        *const u8,
+        *mut *const u16,
+    ) -> Status,
+    languages: *const u8,
+}
+
+impl ComponentName2 {
+    unsafe extern "efiapi" fn get_driver_name(
+        &self,
+        lang: *const u8,
+        name: *mut *const u16,
+    ) -> Status {
+        if lang.is_null() || name.is_null() {
+            return Status::INVALID_PARAMETER;
+        } else if CStr::from_ptr(lang.cast()) != LANGUAGE {
+            return Status::UNSUPPORTED;
+        }
+
+        match Driver::get().imp.driver_name() {
+            Some(v) => {
+                *name = v.as_ptr();
+                Status::SUCCESS
+            }
+            None => Status::UNSUPPORTED,
+        }
+    }
+
+    unsafe extern "efiapi" fn get_controller_name(
+        &self,
+        controller: &Device,
+        child: *const Device,
+        lang: *const u8,
+        name: *mut *const u16,
+    ) -> Status {
+        if lang.is_null() || name.is_null() {
+            return Status::INVALID_PARAMETER;
+        } else if CStr::from_ptr(lang.cast()) != LANGUAGE {
+            return Status::UNSUPPORTED;
+        }
+
+        match Driver::get()
+            .imp
+            .controller_name(controller, child.as_ref())
+        {
+            Some(v) => {
+                *name = v.as_ptr();
+                Status::SUCCESS
+            }
+            None => Status::UNSUPPORTED,
+        }
+    }
+}
+
+unsafe impl Protocol for ComponentName2 {
+    const ID: Guid = Guid::new(
+        0x6a7a5cff,
+        0xe8d9,
+        0x4f70,
+        [0xba, 0xda, 0x75, 0xab, 0x30, 0x25, 0xce, 0x14],
+    );
+}
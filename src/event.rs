@@ -1,6 +1,126 @@
+use crate::{system_table, Owned, Signaled, Status, Tpl};
+use alloc::boxed::Box;
+use bitflags::bitflags;
+use core::time::Duration;
+
+/// Waits until any of `events` is signaled and returns its index.
+///
+/// This is a shortcut to [`super::BootServices::wait_for_event()`]. Only events that are not
+/// [`EventType::NOTIFY_SIGNAL`] can be waited.
+pub fn wait_any(events: &[&Event]) -> Result<usize, Status> {
+    system_table().boot_services().wait_for_event(events)
+}
+
+/// A notification function of [`Event`].
+pub type EventNotify = Box<dyn FnMut(&Event)>;
+
 /// Represents an `EFI_EVENT`.
 ///
-/// The reason this type is not exposed is because it is likely to be changing in the future.
-#[repr(transparent)]
+/// Use [`Event::new()`] or [`Event::timer()`] to create a new event. An event created by ZFI will
+/// be closed automatically when the [`Owned`] is dropped.
+pub struct Event(());
+
+impl Event {
+    /// Creates a new event.
+    ///
+    /// `notify` is required if `ty` contains either [`EventType::NOTIFY_WAIT`] or
+    /// [`EventType::NOTIFY_SIGNAL`] and it will be invoked at `tpl`.
+    pub fn new(
+        ty: EventType,
+        tpl: Tpl,
+        notify: Option<EventNotify>,
+    ) -> Result<Owned<Self>, Status> {
+        system_table().boot_services().create_event(ty, tpl, notify)
+    }
+
+    /// Creates a new timer event without a notification function.
+    ///
+    /// The timer will not start until [`Event::set_relative()`] or [`Event::set_periodic()`] is
+    /// called.
+    pub fn timer() -> Result<Owned<Self>, Status> {
+        Self::new(EventType::TIMER, Tpl::CALLBACK, None)
+    }
+
+    /// Sets the timer to be signaled once after `delay`.
+    pub fn set_relative(&self, delay: Duration) -> Result<(), Status> {
+        system_table()
+            .boot_services()
+            .set_timer(self, TimerDelay::Relative, Self::ticks(delay))
+    }
+
+    /// Sets the timer to be signaled on every `period`.
+    pub fn set_periodic(&self, period: Duration) -> Result<(), Status> {
+        system_table()
+            .boot_services()
+            .set_timer(self, TimerDelay::Periodic, Self::ticks(period))
+    }
+
+    /// Cancels the timer.
+    pub fn cancel_timer(&self) -> Result<(), Status> {
+        system_table()
+            .boot_services()
+            .set_timer(self, TimerDelay::Cancel, 0)
+    }
+
+    /// Signals the event.
+    pub fn signal(&self) -> Result<(), Status> {
+        system_table().boot_services().signal_event(self)
+    }
+
+    /// Returns `true` if the event is in the signaled state. The signaled state will be cleared if
+    /// this method returns `true`.
+    pub fn check(&self) -> Result<bool, Status> {
+        system_table().boot_services().check_event(self)
+    }
+
+    /// Returns a future that will be resolved when the event is signaled.
+    ///
+    /// The returned future must be polled by [`crate::block_on()`].
+    pub fn signaled(&self) -> Signaled<'_> {
+        Signaled(self)
+    }
+
+    pub(crate) unsafe extern "efiapi" fn notify(event: &Event, cx: *mut ()) {
+        let f = cx as *mut EventNotify;
+
+        (*f)(event);
+    }
+
+    pub(crate) fn dtor(e: *mut Self) {
+        // There is nothing we can do if this failed.
+        let _ = unsafe { system_table().boot_services().close_event(e) };
+    }
+
+    /// Converts `d` to 100 nanoseconds unit.
+    fn ticks(d: Duration) -> u64 {
+        (d.as_nanos() / 100).try_into().unwrap_or(u64::MAX)
+    }
+}
+
+bitflags! {
+    /// Type of [`Event`].
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    pub struct EventType: u32 {
+        const TIMER = 0x80000000;
+        const RUNTIME = 0x40000000;
+        const NOTIFY_WAIT = 0x00000100;
+        const NOTIFY_SIGNAL = 0x00000200;
+        const SIGNAL_EXIT_BOOT_SERVICES = 0x00000201;
+        const SIGNAL_VIRTUAL_ADDRESS_CHANGE = 0x60000202;
+    }
+}
+
+/// Represents an `EFI_TIMER_DELAY`.
+#[repr(C)]
 #[derive(Clone, Copy)]
-pub(crate) struct Event(usize);
+pub enum TimerDelay {
+    /// Cancel the previous timer.
+    Cancel,
+
+    /// Signal the event on every trigger time.
+    Periodic,
+
+    /// Signal the event once after trigger time.
+    Relative,
+}
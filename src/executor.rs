@@ -0,0 +1,232 @@
+use crate::{system_table, Event, EventType, InputKey, Owned, SimpleTextInput, Status, Tpl};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::{pin, Pin};
+use core::ptr::{null, null_mut};
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use core::time::Duration;
+
+static mut REACTOR: *mut Reactor = null_mut();
+static WOKEN: AtomicBool = AtomicBool::new(false);
+static WAKE: AtomicPtr<Event> = AtomicPtr::new(null_mut());
+
+/// Runs a future to completion on the current thread.
+///
+/// Any futures provided by ZFI must be polled by this function. When `f` is pending this function
+/// will stop the execution with [`super::BootServices::wait_for_event()`] until one of the events
+/// it is waiting for has been signaled or its [`Waker`] has been woken. The [`Waker`] can be woken
+/// from an event notification function.
+///
+/// # Panics
+/// If the firmware failed to create or wait for the events.
+pub fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    let mut reactor = Reactor {
+        waiting: Vec::new(),
+        signaled: null(),
+    };
+
+    // Create an event for the waker to interrupt WaitForEvent().
+    let wake = Event::new(EventType::empty(), Tpl::CALLBACK, None).unwrap();
+    let prev_wake = WAKE.swap(&*wake as *const Event as *mut Event, Ordering::Relaxed);
+
+    // Set the current reactor. We need to keep the previous one to support nested calls. All access
+    // to the reactor must go through the pointer since the futures also access it.
+    let reactor: *mut Reactor = &mut reactor;
+    let prev = unsafe { REACTOR };
+    let waker = unsafe { Waker::from_raw(RawWaker::new(null(), &WAKER)) };
+    let mut cx = Context::from_waker(&waker);
+
+    unsafe { REACTOR = reactor };
+
+    loop {
+        // Poll the future.
+        WOKEN.store(false, Ordering::Relaxed);
+        wake.check().unwrap();
+        unsafe { (*reactor).waiting.clear() };
+
+        if let Poll::Ready(v) = f.as_mut().poll(&mut cx) {
+            unsafe { REACTOR = prev };
+            WAKE.store(prev_wake, Ordering::Relaxed);
+            break v;
+        }
+
+        unsafe { (*reactor).signaled = null() };
+
+        if WOKEN.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        // Wait for the events. WaitForEvent() will clear the signaled state of the event so we need
+        // to remember it.
+        let waiting = unsafe { &(*reactor).waiting };
+        let events: Vec<&Event> = waiting
+            .iter()
+            .map(|&e| unsafe { &*e })
+            .chain(core::iter::once(&*wake))
+            .collect();
+        let index = system_table()
+            .boot_services()
+            .wait_for_event(&events)
+            .unwrap();
+
+        unsafe { (*reactor).signaled = events[index] };
+    }
+}
+
+/// A future that will be resolved when an event is signaled.
+///
+/// This future cannot be used with the event that has [`crate::EventType::NOTIFY_SIGNAL`].
+pub struct Signaled<'a>(pub(crate) &'a Event);
+
+impl Future for Signaled<'_> {
+    type Output = Result<(), Status>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        poll_event(self.0)
+    }
+}
+
+/// A future that will be resolved once after the specified duration.
+pub struct Timer(Owned<Event>);
+
+impl Timer {
+    /// Creates a new timer that will be resolved once `delay` has elapsed.
+    pub fn after(delay: Duration) -> Result<Self, Status> {
+        let event = Event::timer()?;
+
+        event.set_relative(delay)?;
+
+        Ok(Self(event))
+    }
+}
+
+impl Future for Timer {
+    type Output = Result<(), Status>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        poll_event(&self.0)
+    }
+}
+
+/// A future that will be resolved when a key stroke is available.
+pub struct ReadKey<'a>(pub(crate) &'a SimpleTextInput);
+
+impl Future for ReadKey<'_> {
+    type Output = Result<InputKey, Status>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.0.read_key_stroke() {
+            Ok(Some(v)) => Poll::Ready(Ok(v)),
+            Ok(None) => {
+                register(self.0.wait_for_key());
+                Poll::Pending
+            }
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+/// A future for an asynchronous I/O that accept a [`CompletionToken`].
+///
+/// The future will be resolved with the value of [`CompletionToken`] once the I/O is completed.
+pub struct Completion {
+    token: Box<CompletionToken>,
+    event: Owned<Event>,
+}
+
+impl Completion {
+    /// Creates a new [`Completion`] with an unsignaled token.
+    pub fn new() -> Result<Self, Status> {
+        let event = Event::new(EventType::empty(), Tpl::CALLBACK, None)?;
+        let token = Box::new(CompletionToken {
+            event: &*event,
+            transaction_status: Status::SUCCESS,
+        });
+
+        Ok(Self { token, event })
+    }
+
+    /// Returns a pointer to the token to pass to the I/O function (e.g.
+    /// `EFI_BLOCK_IO2_PROTOCOL.ReadBlocksEx()`).
+    ///
+    /// The pointer is valid as long as this [`Completion`] is alive.
+    pub fn token(&mut self) -> *mut CompletionToken {
+        self.token.as_mut()
+    }
+}
+
+impl Future for Completion {
+    type Output = Result<(), Status>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        match poll_event(&self.event) {
+            Poll::Ready(Ok(_)) => {
+                // The status was written by the firmware.
+                let status = &self.token.transaction_status;
+                let status = unsafe { core::ptr::read_volatile(status) };
+
+                Poll::Ready(status.err_or(()))
+            }
+            v => v,
+        }
+    }
+}
+
+/// A token for asynchronous I/O with the same layout as `EFI_BLOCK_IO2_TOKEN` and
+/// `EFI_DISK_IO2_TOKEN`.
+#[repr(C)]
+pub struct CompletionToken {
+    event: *const Event,
+    transaction_status: Status,
+}
+
+/// Contains the events that the future is waiting for.
+struct Reactor {
+    waiting: Vec<*const Event>,
+    signaled: *const Event,
+}
+
+fn poll_event(event: &Event) -> Poll<Result<(), Status>> {
+    // Check if WaitForEvent() was returned because of this event.
+    let reactor = unsafe { REACTOR.as_mut() }.expect("the future must be polled by block_on()");
+
+    if core::ptr::eq(reactor.signaled, event) {
+        return Poll::Ready(Ok(()));
+    }
+
+    // Check if the event was signaled without WaitForEvent().
+    match event.check() {
+        Ok(true) => Poll::Ready(Ok(())),
+        Ok(false) => {
+            reactor.waiting.push(event);
+            Poll::Pending
+        }
+        Err(e) => Poll::Ready(Err(e)),
+    }
+}
+
+fn register(event: &Event) {
+    let reactor = unsafe { REACTOR.as_mut() }.expect("the future must be polled by block_on()");
+
+    reactor.waiting.push(event);
+}
+
+fn wake() {
+    WOKEN.store(true, Ordering::Relaxed);
+
+    // Interrupt WaitForEvent() in case of we was woken by an event notification function. There is
+    // nothing we can do if this failed.
+    if let Some(e) = unsafe { WAKE.load(Ordering::Relaxed).as_ref() } {
+        let _ = e.signal();
+    }
+}
+
+static WAKER: RawWakerVTable = RawWakerVTable::new(
+    |_| RawWaker::new(null(), &WAKER),
+    |_| wake(),
+    |_| wake(),
+    |_| {},
+);
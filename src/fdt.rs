@@ -0,0 +1,918 @@
+use crate::{system_table, Guid};
+use alloc::vec::Vec;
+use core::slice::from_raw_parts;
+use thiserror::Error;
+
+/// A read-only view of a Flattened Device Tree (FDT).
+#[derive(Clone, Copy)]
+pub struct Fdt<'a> {
+    data: &'a [u8],
+    structs: &'a [u8],
+    strings: &'a [u8],
+}
+
+impl Fdt<'static> {
+    /// GUID of the configuration table for the device tree.
+    pub const GUID: Guid = Guid::new(
+        0xb1b621d5,
+        0xf19c,
+        0x41a5,
+        [0x83, 0x0b, 0xd9, 0x15, 0x2c, 0x69, 0xaa, 0xe0],
+    );
+
+    /// Locates the device tree from the configuration table.
+    pub fn find() -> Result<Self, FdtError> {
+        let ptr: *const u8 = system_table()
+            .find_config_table(&Self::GUID)
+            .ok_or(FdtError::NotFound)?
+            .cast();
+
+        // Get total size.
+        let hdr = unsafe { from_raw_parts(ptr, HEADER_LEN) };
+
+        if be32(hdr, 0) != Some(MAGIC) {
+            return Err(FdtError::InvalidMagic);
+        }
+
+        let len = be32(hdr, 4).unwrap() as usize;
+
+        Self::new(unsafe { from_raw_parts(ptr, len) })
+    }
+}
+
+impl<'a> Fdt<'a> {
+    /// Validates the header of the device tree in `data`.
+    pub fn new(data: &'a [u8]) -> Result<Self, FdtError> {
+        // Check magic and version.
+        let hdr = data.get(..HEADER_LEN).ok_or(FdtError::InvalidLength)?;
+
+        if be32(hdr, 0) != Some(MAGIC) {
+            return Err(FdtError::InvalidMagic);
+        } else if be32(hdr, 20).unwrap() < 16 || be32(hdr, 24).unwrap() > 17 {
+            return Err(FdtError::UnsupportedVersion);
+        }
+
+        // Get blocks.
+        let data = data
+            .get(..(be32(hdr, 4).unwrap() as usize))
+            .ok_or(FdtError::InvalidLength)?;
+        let block = |off: usize, len: usize| {
+            let off = be32(hdr, off).unwrap() as usize;
+            let len = be32(hdr, len).unwrap() as usize;
+
+            off.checked_add(len)
+                .and_then(|end| data.get(off..end))
+                .ok_or(FdtError::InvalidLength)
+        };
+
+        let structs = if be32(hdr, 20).unwrap() >= 17 {
+            block(8, 36)?
+        } else {
+            // Version 16 does not have size_dt_struct so assume the strings block follows the
+            // structure block.
+            let off = be32(hdr, 8).unwrap() as usize;
+            let end = be32(hdr, 12).unwrap() as usize;
+
+            data.get(off..end).ok_or(FdtError::InvalidLength)?
+        };
+
+        let fdt = Self {
+            data,
+            structs,
+            strings: block(12, 32)?,
+        };
+
+        // Check the root node.
+        match read_token(fdt.structs, 0) {
+            Some((Token::BeginNode(_), _)) => Ok(fdt),
+            _ => Err(FdtError::InvalidStructure),
+        }
+    }
+
+    /// Returns the whole device tree, including the header.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn version(&self) -> u32 {
+        be32(self.data, 20).unwrap()
+    }
+
+    /// Returns the physical ID of the boot CPU.
+    pub fn boot_cpuid(&self) -> u32 {
+        be32(self.data, 28).unwrap()
+    }
+
+    /// Returns an iterator over the memory reservation block.
+    pub fn reserved_memory(&self) -> impl Iterator<Item = FdtRegion> + 'a {
+        let off = be32(self.data, 16).unwrap() as usize;
+
+        self.data
+            .get(off..)
+            .unwrap_or(&[])
+            .chunks_exact(16)
+            .map(|v| FdtRegion {
+                address: u64::from_be_bytes(v[..8].try_into().unwrap()),
+                size: u64::from_be_bytes(v[8..].try_into().unwrap()),
+            })
+            .take_while(|r| r.address != 0 || r.size != 0)
+    }
+
+    pub fn root(&self) -> FdtNode<'a> {
+        // The root node was validated when constructed.
+        let (name, content) = match read_token(self.structs, 0) {
+            Some((Token::BeginNode(n), c)) => (n, c),
+            _ => unreachable!(),
+        };
+
+        FdtNode {
+            structs: self.structs,
+            strings: self.strings,
+            name,
+            content,
+            cells: (2, 1),
+        }
+    }
+
+    /// Finds a node by its absolute path (e.g. `/cpus/cpu@0`). The unit address can be omitted if
+    /// the name is unique.
+    pub fn find_node(&self, path: &str) -> Option<FdtNode<'a>> {
+        self.root().find(path)
+    }
+
+    /// Returns an iterator over all nodes that compatible with `compatible`.
+    pub fn find_compatible<'b>(&self, compatible: &'b str) -> impl Iterator<Item = FdtNode<'a>> + 'b
+    where
+        'a: 'b,
+    {
+        FdtDescendants {
+            stack: alloc::vec![self.root().children()],
+        }
+        .filter(move |n| n.is_compatible(compatible))
+    }
+
+    /// Returns the `/chosen` node.
+    pub fn chosen(&self) -> Option<FdtNode<'a>> {
+        self.find_node("/chosen")
+    }
+
+    /// Returns `bootargs` from `/chosen`.
+    pub fn bootargs(&self) -> Option<&'a str> {
+        self.chosen()?.property("bootargs")?.as_str()
+    }
+
+    /// Returns `stdout-path` from `/chosen`.
+    pub fn stdout_path(&self) -> Option<&'a str> {
+        self.chosen()?.property("stdout-path")?.as_str()
+    }
+
+    /// Returns `linux,initrd-start` and `linux,initrd-end` from `/chosen`.
+    pub fn initrd(&self) -> Option<(u64, u64)> {
+        let chosen = self.chosen()?;
+        let start = chosen.property("linux,initrd-start")?.as_u64()?;
+        let end = chosen.property("linux,initrd-end")?.as_u64()?;
+
+        Some((start, end))
+    }
+}
+
+/// A node in [`Fdt`].
+#[derive(Clone, Copy)]
+pub struct FdtNode<'a> {
+    structs: &'a [u8],
+    strings: &'a [u8],
+    name: &'a [u8],
+    content: usize,
+    cells: (u32, u32),
+}
+
+impl<'a> FdtNode<'a> {
+    /// Returns the name of this node, including the unit address. Returns an empty string for the
+    /// root node or if the name is not a valid UTF-8.
+    pub fn name(&self) -> &'a str {
+        core::str::from_utf8(self.name).unwrap_or("")
+    }
+
+    pub fn properties(&self) -> FdtProperties<'a> {
+        FdtProperties {
+            structs: self.structs,
+            strings: self.strings,
+            off: self.content,
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<FdtProperty<'a>> {
+        self.properties().find(|p| p.name == name)
+    }
+
+    pub fn children(&self) -> FdtChildren<'a> {
+        FdtChildren {
+            structs: self.structs,
+            strings: self.strings,
+            off: self.content,
+            cells: (self.address_cells(), self.size_cells()),
+        }
+    }
+
+    /// Returns a child node with `name`. The unit address can be omitted if the name is unique.
+    pub fn child(&self, name: &str) -> Option<FdtNode<'a>> {
+        self.children().find(|c| {
+            let n = c.name();
+
+            n == name || (!name.contains('@') && n.split('@').next() == Some(name))
+        })
+    }
+
+    /// Finds a descendant node by a path relative to this node.
+    pub fn find(&self, path: &str) -> Option<FdtNode<'a>> {
+        path.split('/')
+            .filter(|v| !v.is_empty())
+            .try_fold(*self, |n, c| n.child(c))
+    }
+
+    /// Returns an iterator over the strings in `compatible` property.
+    pub fn compatible(&self) -> impl Iterator<Item = &'a str> {
+        self.property("compatible")
+            .map(|p| p.as_str_list())
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn is_compatible(&self, compatible: &str) -> bool {
+        self.compatible().any(|v| v == compatible)
+    }
+
+    /// Returns an iterator over the regions in `reg` property. Returns [`None`] if this node does
+    /// not have `reg` property or the address or the size is larger than 64-bit.
+    pub fn reg(&self) -> Option<FdtReg<'a>> {
+        let (ac, sc) = self.cells;
+
+        if ac > 2 || sc > 2 {
+            return None;
+        }
+
+        Some(FdtReg {
+            data: self.property("reg")?.value,
+            address_cells: ac as usize,
+            size_cells: sc as usize,
+        })
+    }
+
+    /// Returns `#address-cells` of this node, which is used to parse `reg` of the children.
+    pub fn address_cells(&self) -> u32 {
+        self.property("#address-cells")
+            .and_then(|p| p.as_u32())
+            .unwrap_or(2)
+    }
+
+    /// Returns `#size-cells` of this node, which is used to parse `reg` of the children.
+    pub fn size_cells(&self) -> u32 {
+        self.property("#size-cells")
+            .and_then(|p| p.as_u32())
+            .unwrap_or(1)
+    }
+}
+
+/// An iterator over the properties of [`FdtNode`].
+pub struct FdtProperties<'a> {
+    structs: &'a [u8],
+    strings: &'a [u8],
+    off: usize,
+}
+
+impl<'a> Iterator for FdtProperties<'a> {
+    type Item = FdtProperty<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (name, value) = match read_token(self.structs, self.off)? {
+            (Token::Prop(name, value), next) => {
+                self.off = next;
+                (name, value)
+            }
+            _ => return None,
+        };
+
+        Some(FdtProperty {
+            name: string_at(self.strings, name)?,
+            value,
+        })
+    }
+}
+
+/// An iterator over the children of [`FdtNode`].
+pub struct FdtChildren<'a> {
+    structs: &'a [u8],
+    strings: &'a [u8],
+    off: usize,
+    cells: (u32, u32),
+}
+
+impl<'a> Iterator for FdtChildren<'a> {
+    type Item = FdtNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (token, next) = read_token(self.structs, self.off)?;
+
+            match token {
+                Token::BeginNode(name) => {
+                    self.off = skip_node(self.structs, next)?;
+
+                    break Some(FdtNode {
+                        structs: self.structs,
+                        strings: self.strings,
+                        name,
+                        content: next,
+                        cells: self.cells,
+                    });
+                }
+                Token::Prop(_, _) => self.off = next,
+                Token::EndNode | Token::End => break None,
+            }
+        }
+    }
+}
+
+/// A depth-first iterator over all nodes.
+struct FdtDescendants<'a> {
+    stack: Vec<FdtChildren<'a>>,
+}
+
+impl<'a> Iterator for FdtDescendants<'a> {
+    type Item = FdtNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(v) => {
+                    self.stack.push(v.children());
+                    break Some(v);
+                }
+                None => drop(self.stack.pop()),
+            }
+        }
+    }
+}
+
+/// A property of [`FdtNode`].
+#[derive(Clone, Copy)]
+pub struct FdtProperty<'a> {
+    name: &'a str,
+    value: &'a [u8],
+}
+
+impl<'a> FdtProperty<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Returns [`None`] if the value is not a 32-bit integer.
+    pub fn as_u32(&self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.value.try_into().ok()?))
+    }
+
+    /// Returns [`None`] if the value is not a 32-bit or 64-bit integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value.len() {
+            4 => self.as_u32().map(u64::from),
+            8 => Some(u64::from_be_bytes(self.value.try_into().unwrap())),
+            _ => None,
+        }
+    }
+
+    /// Returns [`None`] if the value is not a NUL-terminated UTF-8 string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let (&last, s) = self.value.split_last()?;
+
+        if last != 0 || s.contains(&0) {
+            return None;
+        }
+
+        core::str::from_utf8(s).ok()
+    }
+
+    /// Returns an iterator over the strings in the string list. Any string that is not a valid
+    /// UTF-8 will be skipped.
+    pub fn as_str_list(&self) -> impl Iterator<Item = &'a str> {
+        let value = self.value.strip_suffix(&[0]).unwrap_or(self.value);
+
+        value
+            .split(|&b| b == 0)
+            .filter_map(|v| core::str::from_utf8(v).ok())
+    }
+}
+
+/// An iterator over the regions in `reg` property.
+pub struct FdtReg<'a> {
+    data: &'a [u8],
+    address_cells: usize,
+    size_cells: usize,
+}
+
+impl Iterator for FdtReg<'_> {
+    type Item = FdtRegion;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let read = |data: &[u8]| {
+            data.chunks_exact(4)
+                .fold(0u64, |r, v| (r << 32) | u64::from(be32(v, 0).unwrap()))
+        };
+
+        let alen = self.address_cells * 4;
+        let len = alen + self.size_cells * 4;
+        let data = self.data.get(..len)?;
+
+        if len == 0 {
+            return None;
+        }
+
+        self.data = &self.data[len..];
+
+        Some(FdtRegion {
+            address: read(&data[..alen]),
+            size: read(&data[alen..]),
+        })
+    }
+}
+
+/// A memory region from `reg` property or the memory reservation block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdtRegion {
+    address: u64,
+    size: u64,
+}
+
+impl FdtRegion {
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// A builder to create a new device tree or patch an existing one.
+///
+/// Blocks of the produced device tree is always in the following order: header, memory
+/// reservation block, structure block and strings block.
+pub struct FdtBuilder {
+    boot_cpuid: u32,
+    reserved: Vec<u8>,
+    structs: Vec<u8>,
+    strings: Vec<u8>,
+}
+
+impl FdtBuilder {
+    /// Creates a builder with an empty root node.
+    pub fn new() -> Self {
+        let mut structs = Vec::new();
+
+        push_node(&mut structs, 0, "");
+        structs.extend_from_slice(&END.to_be_bytes());
+
+        Self {
+            boot_cpuid: 0,
+            reserved: alloc::vec![0; 16],
+            structs,
+            strings: Vec::new(),
+        }
+    }
+
+    /// Creates a builder from the content of `fdt`.
+    pub fn from_fdt(fdt: &Fdt) -> Self {
+        let mut reserved: Vec<u8> = fdt
+            .reserved_memory()
+            .flat_map(|r| {
+                let mut v = [0; 16];
+
+                v[..8].copy_from_slice(&r.address.to_be_bytes());
+                v[8..].copy_from_slice(&r.size.to_be_bytes());
+                v
+            })
+            .collect();
+
+        reserved.extend_from_slice(&[0; 16]);
+
+        Self {
+            boot_cpuid: fdt.boot_cpuid(),
+            reserved,
+            structs: fdt.structs.to_vec(),
+            strings: fdt.strings.to_vec(),
+        }
+    }
+
+    /// Sets a property on the node at `path`. The property will be replaced if it is already
+    /// exists.
+    pub fn set_property(&mut self, path: &str, name: &str, value: &[u8]) -> Result<(), FdtError> {
+        let node = self.node(path)?;
+
+        // Look for the existing property.
+        let mut off = node.content;
+        let end = loop {
+            match read_token(&self.structs, off).ok_or(FdtError::InvalidStructure)? {
+                (Token::Prop(n, _), next) => {
+                    if string_at(&self.strings, n) == Some(name) {
+                        break next;
+                    }
+
+                    off = next;
+                }
+                _ => {
+                    off = node.content;
+                    break off;
+                }
+            }
+        };
+
+        // Replace or insert the property.
+        let mut prop = Vec::with_capacity(12 + value.len() + 3);
+        let len: u32 = value
+            .len()
+            .try_into()
+            .map_err(|_| FdtError::InvalidLength)?;
+        let name = self.string(name)?;
+
+        prop.extend_from_slice(&PROP.to_be_bytes());
+        prop.extend_from_slice(&len.to_be_bytes());
+        prop.extend_from_slice(&name.to_be_bytes());
+        prop.extend_from_slice(value);
+        prop.resize(align(prop.len()), 0);
+
+        self.structs.splice(off..end, prop);
+
+        Ok(())
+    }
+
+    /// Removes a property from the node at `path`. This method does nothing if the property does
+    /// not exists.
+    pub fn remove_property(&mut self, path: &str, name: &str) -> Result<(), FdtError> {
+        let node = self.node(path)?;
+        let mut off = node.content;
+
+        while let Some((Token::Prop(n, _), next)) = read_token(&self.structs, off) {
+            if string_at(&self.strings, n) == Some(name) {
+                self.structs.drain(off..next);
+                break;
+            }
+
+            off = next;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a node named `name` under the node at `parent`. This method does nothing if the node
+    /// is already exists.
+    pub fn add_node(&mut self, parent: &str, name: &str) -> Result<(), FdtError> {
+        let node = self.node(parent)?;
+
+        if node.children().any(|c| c.name() == name) {
+            return Ok(());
+        }
+
+        // Insert the node before END_NODE of the parent.
+        let end = skip_node(&self.structs, node.content).ok_or(FdtError::InvalidStructure)? - 4;
+
+        push_node(&mut self.structs, end, name);
+
+        Ok(())
+    }
+
+    /// Adds a memory reservation.
+    pub fn add_reserved_memory(&mut self, address: u64, size: u64) {
+        let off = self.reserved.len() - 16;
+        let mut v = [0; 16];
+
+        v[..8].copy_from_slice(&address.to_be_bytes());
+        v[8..].copy_from_slice(&size.to_be_bytes());
+
+        self.reserved.splice(off..off, v);
+    }
+
+    /// Sets `bootargs` in `/chosen`. `/chosen` will be created if it does not exists.
+    pub fn set_bootargs(&mut self, args: &str) -> Result<(), FdtError> {
+        let mut value = Vec::with_capacity(args.len() + 1);
+
+        value.extend_from_slice(args.as_bytes());
+        value.push(0);
+
+        self.add_node("/", "chosen")?;
+        self.set_property("/chosen", "bootargs", &value)
+    }
+
+    /// Sets `linux,initrd-start` and `linux,initrd-end` in `/chosen`. `/chosen` will be created if
+    /// it does not exists.
+    pub fn set_initrd(&mut self, start: u64, end: u64) -> Result<(), FdtError> {
+        self.add_node("/", "chosen")?;
+        self.set_property("/chosen", "linux,initrd-start", &start.to_be_bytes())?;
+        self.set_property("/chosen", "linux,initrd-end", &end.to_be_bytes())
+    }
+
+    /// Produces the device tree.
+    pub fn build(&self) -> Vec<u8> {
+        let rsv = HEADER_LEN;
+        let structs = rsv + self.reserved.len();
+        let strings = structs + self.structs.len();
+        let total = strings + self.strings.len();
+        let mut data = Vec::with_capacity(total);
+
+        for v in [
+            MAGIC,
+            total as u32,
+            structs as u32,
+            strings as u32,
+            rsv as u32,
+            17,
+            16,
+            self.boot_cpuid,
+            self.strings.len() as u32,
+            self.structs.len() as u32,
+        ] {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+
+        data.extend_from_slice(&self.reserved);
+        data.extend_from_slice(&self.structs);
+        data.extend_from_slice(&self.strings);
+        data
+    }
+
+    fn node(&self, path: &str) -> Result<FdtNode<'_>, FdtError> {
+        let (name, content) = match read_token(&self.structs, 0) {
+            Some((Token::BeginNode(n), c)) => (n, c),
+            _ => return Err(FdtError::InvalidStructure),
+        };
+
+        let root = FdtNode {
+            structs: &self.structs,
+            strings: &self.strings,
+            name,
+            content,
+            cells: (2, 1),
+        };
+
+        root.find(path).ok_or(FdtError::NodeNotFound)
+    }
+
+    fn string(&mut self, name: &str) -> Result<u32, FdtError> {
+        // Look for the existing string.
+        let mut off = 0;
+
+        for s in self.strings.split(|&b| b == 0) {
+            if s == name.as_bytes() && off < self.strings.len() {
+                return Ok(off as u32);
+            }
+
+            off += s.len() + 1;
+        }
+
+        // Append a new string.
+        let off = self.strings.len();
+
+        self.strings.extend_from_slice(name.as_bytes());
+        self.strings.push(0);
+
+        off.try_into().map_err(|_| FdtError::InvalidLength)
+    }
+}
+
+impl Default for FdtBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Represents an error when parsing or building a device tree is failed.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FdtError {
+    #[error("no device tree")]
+    NotFound,
+
+    #[error("invalid magic")]
+    InvalidMagic,
+
+    #[error("unsupported version")]
+    UnsupportedVersion,
+
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("invalid structure block")]
+    InvalidStructure,
+
+    #[error("node not found")]
+    NodeNotFound,
+}
+
+enum Token<'a> {
+    BeginNode(&'a [u8]),
+    EndNode,
+    Prop(u32, &'a [u8]),
+    End,
+}
+
+const MAGIC: u32 = 0xd00dfeed;
+const HEADER_LEN: usize = 40;
+const BEGIN_NODE: u32 = 1;
+const END_NODE: u32 = 2;
+const PROP: u32 = 3;
+const NOP: u32 = 4;
+const END: u32 = 9;
+
+/// Reads a token at `off` from the structure block. Returns the token and the offset of the next
+/// token.
+fn read_token(structs: &[u8], mut off: usize) -> Option<(Token<'_>, usize)> {
+    loop {
+        let token = be32(structs, off)?;
+
+        off += 4;
+
+        match token {
+            BEGIN_NODE => {
+                let name = structs.get(off..)?;
+                let name = &name[..name.iter().position(|&b| b == 0)?];
+
+                break Some((Token::BeginNode(name), align(off + name.len() + 1)));
+            }
+            END_NODE => break Some((Token::EndNode, off)),
+            PROP => {
+                let len = be32(structs, off)? as usize;
+                let name = be32(structs, off + 4)?;
+                let value = structs.get((off + 8)..(off + 8).checked_add(len)?)?;
+
+                break Some((Token::Prop(name, value), align(off + 8 + len)));
+            }
+            NOP => continue,
+            END => break Some((Token::End, off)),
+            _ => break None,
+        }
+    }
+}
+
+/// Returns the offset after END_NODE of the node that has `content` as the content offset.
+fn skip_node(structs: &[u8], mut content: usize) -> Option<usize> {
+    let mut depth = 1;
+
+    loop {
+        let (token, next) = read_token(structs, content)?;
+
+        match token {
+            Token::BeginNode(_) => depth += 1,
+            Token::EndNode => {
+                depth -= 1;
+
+                if depth == 0 {
+                    break Some(next);
+                }
+            }
+            Token::Prop(_, _) => {}
+            Token::End => break None,
+        }
+
+        content = next;
+    }
+}
+
+/// Inserts an empty node at `off`.
+fn push_node(structs: &mut Vec<u8>, off: usize, name: &str) {
+    let mut node = Vec::with_capacity(8 + name.len() + 4);
+
+    node.extend_from_slice(&BEGIN_NODE.to_be_bytes());
+    node.extend_from_slice(name.as_bytes());
+    node.push(0);
+    node.resize(align(node.len()), 0);
+    node.extend_from_slice(&END_NODE.to_be_bytes());
+
+    structs.splice(off..off, node);
+}
+
+fn string_at(strings: &[u8], off: u32) -> Option<&str> {
+    let s = strings.get((off as usize)..)?;
+    let s = &s[..s.iter().position(|&b| b == 0)?];
+
+    core::str::from_utf8(s).ok()
+}
+
+fn be32(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(off..off.checked_add(4)?)?.try_into().unwrap(),
+    ))
+}
+
+fn align(v: usize) -> usize {
+    (v + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let mut b = FdtBuilder::new();
+
+        b.set_property("/", "#address-cells", &2u32.to_be_bytes())
+            .unwrap();
+        b.set_property("/", "#size-cells", &2u32.to_be_bytes())
+            .unwrap();
+        b.add_node("/", "memory@40000000").unwrap();
+        b.set_property("/memory@40000000", "device_type", b"memory\0")
+            .unwrap();
+        b.set_property(
+            "/memory@40000000",
+            "reg",
+            &[
+                0, 0, 0, 0, 0x40, 0, 0, 0, //
+                0, 0, 0, 0, 0x08, 0, 0, 0,
+            ],
+        )
+        .unwrap();
+        b.add_node("/", "pl011@9000000").unwrap();
+        b.set_property(
+            "/pl011@9000000",
+            "compatible",
+            b"arm,pl011\0arm,primecell\0",
+        )
+        .unwrap();
+        b.add_reserved_memory(0x1000, 0x2000);
+        b.set_bootargs("console=ttyAMA0").unwrap();
+        b.set_initrd(0x48000000, 0x49000000).unwrap();
+
+        // Check the result.
+        let data = b.build();
+        let fdt = Fdt::new(&data).unwrap();
+        let mem = fdt.find_node("/memory").unwrap();
+        let reg: Vec<FdtRegion> = mem.reg().unwrap().collect();
+
+        assert_eq!(mem.name(), "memory@40000000");
+        assert_eq!(
+            mem.property("device_type").unwrap().as_str(),
+            Some("memory")
+        );
+        assert_eq!(reg.len(), 1);
+        assert_eq!(reg[0].address(), 0x40000000);
+        assert_eq!(reg[0].size(), 0x8000000);
+        assert_eq!(
+            fdt.find_compatible("arm,primecell")
+                .map(|n| n.name())
+                .collect::<Vec<_>>(),
+            ["pl011@9000000"]
+        );
+        assert_eq!(fdt.bootargs(), Some("console=ttyAMA0"));
+        assert_eq!(fdt.initrd(), Some((0x48000000, 0x49000000)));
+        assert_eq!(
+            fdt.reserved_memory().collect::<Vec<_>>(),
+            [FdtRegion {
+                address: 0x1000,
+                size: 0x2000
+            }]
+        );
+        assert!(fdt.find_node("/cpus").is_none());
+
+        // Patch the existing device tree.
+        let mut b = FdtBuilder::from_fdt(&fdt);
+
+        b.set_bootargs("console=ttyAMA0 root=/dev/vda1").unwrap();
+        b.remove_property("/memory", "device_type").unwrap();
+
+        let data = b.build();
+        let fdt = Fdt::new(&data).unwrap();
+
+        assert_eq!(fdt.bootargs(), Some("console=ttyAMA0 root=/dev/vda1"));
+        assert_eq!(fdt.initrd(), Some((0x48000000, 0x49000000)));
+        assert!(fdt
+            .find_node("/memory")
+            .unwrap()
+            .property("device_type")
+            .is_none());
+        assert_eq!(fdt.root().children().count(), 3);
+    }
+
+    #[test]
+    fn version16() {
+        let mut b = FdtBuilder::new();
+
+        b.set_bootargs("console=ttyAMA0").unwrap();
+
+        // Downgrade to version 16, which does not have size_dt_struct.
+        let mut data = b.build();
+
+        data[20..24].copy_from_slice(&16u32.to_be_bytes());
+        data[36..40].copy_from_slice(&u32::MAX.to_be_bytes());
+
+        let fdt = Fdt::new(&data).unwrap();
+
+        assert_eq!(fdt.bootargs(), Some("console=ttyAMA0"));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Fdt::new(&[0; 8]).err(), Some(FdtError::InvalidLength));
+        assert_eq!(Fdt::new(&[0; 40]).err(), Some(FdtError::InvalidMagic));
+    }
+}
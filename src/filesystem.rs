@@ -1,9 +1,8 @@
-use crate::{Dtor, EfiStr, Guid, Owned, Status, Time};
+use crate::{Dtor, EfiStr, Guid, Owned, Protocol, Status, Time};
 use alloc::alloc::{alloc, dealloc, handle_alloc_error};
 use alloc::boxed::Box;
 use bitflags::bitflags;
 use core::alloc::Layout;
-use core::mem::zeroed;
 use core::ptr::null_mut;
 use thiserror::Error;
 
@@ -15,13 +14,6 @@ pub struct SimpleFileSystem {
 }
 
 impl SimpleFileSystem {
-    pub const ID: Guid = Guid::new(
-        0x0964e5b22,
-        0x6459,
-        0x11d2,
-        [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
-    );
-
     /// Opens the root directory on a volume.
     pub fn open(&self) -> Result<Owned<File>, Status> {
         let mut root = null_mut();
@@ -35,6 +27,15 @@ impl SimpleFileSystem {
     }
 }
 
+unsafe impl Protocol for SimpleFileSystem {
+    const ID: Guid = Guid::new(
+        0x0964e5b22,
+        0x6459,
+        0x11d2,
+        [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
+    );
+}
+
 /// Represents an `EFI_FILE_PROTOCOL`.
 #[repr(C)]
 pub struct File {
@@ -212,9 +213,9 @@ impl File {
 
         // Update the info.
         info.set_file_size(len);
-        info.set_create_time(unsafe { zeroed() });
-        info.set_last_accessed(unsafe { zeroed() });
-        info.set_last_modified(unsafe { zeroed() });
+        info.set_create_time(Time::ZERO);
+        info.set_last_accessed(Time::ZERO);
+        info.set_last_modified(Time::ZERO);
 
         // Set the info.
         let len = 0x50 + info.file_name.len() * 2;
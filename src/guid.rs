@@ -1,5 +1,8 @@
+use core::fmt::{Display, Formatter};
+
 /// An implementation of `EFI_GUID`.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Guid {
     data1: u32,
     data2: u16,
@@ -16,4 +19,27 @@ impl Guid {
             data4,
         }
     }
+
+    /// Creates a GUID from its binary representation, which has the first three fields in
+    /// little-endian.
+    pub const fn from_bytes(b: [u8; 16]) -> Self {
+        Self {
+            data1: u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            data2: u16::from_le_bytes([b[4], b[5]]),
+            data3: u16::from_le_bytes([b[6], b[7]]),
+            data4: [b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]],
+        }
+    }
+}
+
+impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let d = &self.data4;
+
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            self.data1, self.data2, self.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+        )
+    }
 }
@@ -0,0 +1,111 @@
+use crate::{
+    page_count, system_table, AllocateType, MemoryMap, MemoryType, RuntimeServices, Status,
+    DEBUG_WRITER,
+};
+use core::alloc::Layout;
+use core::mem::forget;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static EXITED: AtomicBool = AtomicBool::new(false);
+static HEAP_NEXT: AtomicUsize = AtomicUsize::new(0);
+static HEAP_END: AtomicUsize = AtomicUsize::new(0);
+
+/// Terminates all boot services and switches ZFI into the post-boot mode. Returns the final memory
+/// map and the runtime services.
+///
+/// `heap` is the number of bytes to reserve for the allocations after this call. Specify zero if
+/// you don't need any allocation after this call.
+///
+/// Once this function has invoked `EFI_BOOT_SERVICES.ExitBootServices()` ZFI will be in the
+/// post-boot mode even if it is failed, which mean:
+///
+/// - [`crate::PoolAllocator`] allocates from the reserved heap and never free the memory.
+/// - [`crate::println!`], [`crate::eprintln!`] and [`crate::debugln!`] do nothing.
+/// - The debug writer will be leaked.
+/// - All types that free its resources when dropped (e.g. [`crate::Pages`]) will leak the resources
+///   instead.
+///
+/// # Safety
+/// Any objects that were obtained from the boot services (e.g. protocol interfaces) must not be used
+/// after this call.
+pub unsafe fn exit_boot_services(
+    heap: usize,
+) -> Result<(MemoryMap, &'static RuntimeServices), Status> {
+    let st = system_table();
+    let bs = st.boot_services();
+
+    if boot_services_exited() {
+        return Err(Status::UNSUPPORTED);
+    }
+
+    // Reserve the heap. This must be done before getting the memory map.
+    let heap = match heap {
+        0 => None,
+        v => Some(bs.allocate_pages(
+            AllocateType::AnyPages,
+            MemoryType::LoaderData,
+            page_count(v),
+            0,
+        )?),
+    };
+
+    // Get the memory map. We can't allocate any memory after this.
+    let mut map = bs.get_memory_map()?;
+
+    // Switch to post-boot mode. The firmware may partially shutdown the boot services even if
+    // ExitBootServices() failed.
+    if let Some(v) = heap {
+        let v = v.leak().as_mut_ptr_range();
+
+        HEAP_NEXT.store(v.start as usize, Ordering::Relaxed);
+        HEAP_END.store(v.end as usize, Ordering::Relaxed);
+    }
+
+    EXITED.store(true, Ordering::Release);
+
+    #[allow(static_mut_refs)]
+    forget(DEBUG_WRITER.take());
+
+    // Exit the boot services. Only GetMemoryMap() is allowed if it is failed.
+    loop {
+        match bs.exit_boot_services(map.key()) {
+            Ok(_) => break,
+            Err(Status::INVALID_PARAMETER) => bs.read_memory_map(&mut map)?,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok((map, st.runtime_services()))
+}
+
+/// Returns `true` if [`exit_boot_services()`] has been called.
+pub fn boot_services_exited() -> bool {
+    EXITED.load(Ordering::Acquire)
+}
+
+/// Allocates a memory from the heap that was reserved by [`exit_boot_services()`].
+pub(crate) fn heap_alloc(layout: Layout) -> *mut u8 {
+    let mut next = HEAP_NEXT.load(Ordering::Relaxed);
+    let end = HEAP_END.load(Ordering::Relaxed);
+
+    loop {
+        // Get the aligned address.
+        let addr = match next.checked_next_multiple_of(layout.align()) {
+            Some(v) => v,
+            None => return null_mut(),
+        };
+
+        // Check if we have enough space.
+        let new = match addr.checked_add(layout.size()) {
+            Some(v) if v <= end => v,
+            _ => return null_mut(),
+        };
+
+        // Allocate.
+        match HEAP_NEXT.compare_exchange_weak(next, new, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break addr as *mut u8,
+            Err(v) => next = v,
+        }
+    }
+}
@@ -1,25 +1,63 @@
-use crate::{system_table, Device, Guid, OpenProtocolAttributes, Path, SystemTable, IMAGE};
+use crate::{
+    boot_services_exited, current_image, system_table, Device, EfiStr, Guid, MemoryType,
+    OpenProtocolAttributes, Path, Protocol, Status, SystemTable, IMAGE,
+};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::mem::forget;
+use core::ops::Deref;
 use core::ptr::null;
+use core::slice::from_raw_parts;
+use thiserror::Error;
 
 /// Represents an `EFI_HANDLE` for the image.
 pub struct Image(());
 
 impl Image {
+    /// Loads an image from `path` as a child of the current image.
+    pub fn load_from_path(path: &Path) -> Result<ChildImage, LoadError> {
+        Self::load(Some(path), None)
+    }
+
+    /// Loads an image from `data` as a child of the current image. `path` is the device path where
+    /// `data` was loaded from, if any.
+    pub fn load_from_buffer(data: &[u8], path: Option<&Path>) -> Result<ChildImage, LoadError> {
+        Self::load(path, Some(data))
+    }
+
     /// Gets the `EFI_LOADED_IMAGE_PROTOCOL` from this image.
     pub fn proto(&self) -> &LoadedImage {
-        static ID: Guid = Guid::new(
-            0x5B1B31A1,
-            0x9562,
-            0x11d2,
-            [0x8E, 0x3F, 0x00, 0xA0, 0xC9, 0x69, 0x72, 0x3B],
-        );
+        unsafe { &*self.proto_ptr() }
+    }
 
+    /// # Safety
+    /// `f` must be able to unload this image.
+    pub(crate) unsafe fn set_unload(&self, f: unsafe extern "efiapi" fn(&Image) -> Status) {
+        (*self.proto_ptr()).unload = Some(f);
+    }
+
+    fn load(path: Option<&Path>, data: Option<&[u8]>) -> Result<ChildImage, LoadError> {
+        let result = system_table()
+            .boot_services()
+            .load_image(false, current_image(), path, data);
+
+        match result {
+            (Status::SUCCESS, Some(v)) => Ok(ChildImage::new(v)),
+            (Status::SECURITY_VIOLATION, Some(v)) => {
+                Err(LoadError::SecurityViolation(ChildImage::new(v)))
+            }
+            (Status::SUCCESS, None) => Err(LoadError::LoadFailed(Status::COMPROMISED_DATA)),
+            (e, _) => Err(LoadError::LoadFailed(e)),
+        }
+    }
+
+    fn proto_ptr(&self) -> *mut LoadedImage {
         let proto = unsafe {
             system_table()
                 .boot_services()
                 .open_protocol(
                     self as *const Image as *const (),
-                    &ID,
+                    &LoadedImage::ID,
                     IMAGE.cast(),
                     null(),
                     OpenProtocolAttributes::GET_PROTOCOL,
@@ -27,7 +65,153 @@ impl Image {
                 .unwrap()
         };
 
-        unsafe { &*(proto as *const LoadedImage) }
+        proto as *mut LoadedImage
+    }
+}
+
+/// An image that was loaded by the current image but not started yet.
+///
+/// The image will be unloaded when dropped.
+pub struct ChildImage {
+    image: &'static Image,
+    options: Option<Vec<u8>>,
+}
+
+impl ChildImage {
+    fn new(image: &'static Image) -> Self {
+        Self {
+            image,
+            options: None,
+        }
+    }
+
+    /// Sets the load options to pass to the image.
+    pub fn set_load_options(&mut self, options: Vec<u8>) {
+        let proto = self.image.proto_ptr();
+
+        unsafe {
+            (*proto).load_options_size = options.len().try_into().unwrap();
+            (*proto).load_options = options.as_ptr().cast();
+        }
+
+        self.options = Some(options);
+    }
+
+    /// Sets a command line to pass to the image as the load options.
+    pub fn set_command_line<S: AsRef<EfiStr>>(&mut self, cmd: S) {
+        let cmd: &[u8] = cmd.as_ref().as_ref();
+
+        self.set_load_options(cmd.to_vec());
+    }
+
+    /// Starts the image. Returns the exit code and exit data of the image.
+    ///
+    /// The image will be unloaded if it failed to start or exited with an error.
+    pub fn start(mut self) -> (Status, Option<ExitData>) {
+        // The application image will be unloaded by the firmware when it is exited.
+        let app = self.image.proto().image_code_type == MemoryType::LoaderCode as u32;
+        let result = unsafe { system_table().boot_services().start_image(self.image) };
+
+        // Let Drop unload the image if it was failed. The firmware already unloaded the image if it
+        // was started but exited with an error, in which case UnloadImage() will fail harmlessly.
+        if result.0 != Status::SUCCESS {
+            return result;
+        }
+
+        let options = self.options.take();
+
+        forget(self);
+
+        // The driver image may still reference the load options.
+        if !app {
+            forget(options);
+        }
+
+        result
+    }
+
+    /// Unloads the image.
+    pub fn unload(self) -> Result<(), Status> {
+        let result = unsafe { system_table().boot_services().unload_image(self.image) };
+
+        forget(self);
+
+        result
+    }
+}
+
+impl Drop for ChildImage {
+    fn drop(&mut self) {
+        if boot_services_exited() {
+            return;
+        }
+
+        let _ = unsafe { system_table().boot_services().unload_image(self.image) };
+    }
+}
+
+impl Deref for ChildImage {
+    type Target = Image;
+
+    fn deref(&self) -> &Self::Target {
+        self.image
+    }
+}
+
+impl Debug for ChildImage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ChildImage")
+            .field(&(self.image as *const Image))
+            .finish()
+    }
+}
+
+/// Represents an error when [`Image::load_from_path()`] or [`Image::load_from_buffer()`] fails.
+#[derive(Debug, Error)]
+pub enum LoadError {
+    /// The image was loaded but failed the security check. The image cannot be started and will be
+    /// unloaded when the [`ChildImage`] is dropped.
+    #[error("the image failed the security check")]
+    SecurityViolation(ChildImage),
+
+    #[error(transparent)]
+    LoadFailed(Status),
+}
+
+/// Exit data of an image, which was allocated by the image.
+pub struct ExitData {
+    ptr: *mut u16,
+    len: usize, // In bytes.
+}
+
+impl ExitData {
+    pub(crate) unsafe fn new(ptr: *mut u16, len: usize) -> Self {
+        Self { ptr, len }
+    }
+
+    /// Returns the description of the exit data. Returns [`None`] if the exit data does not start
+    /// with a NUL-terminated string.
+    pub fn description(&self) -> Option<&EfiStr> {
+        let data = unsafe { from_raw_parts(self.ptr, self.len / 2) };
+        let len = data.iter().position(|&c| c == 0)?;
+
+        // SAFETY: We already ensure the string is NUL-terminated. The firmware is responsible for
+        // the rest.
+        Some(unsafe { EfiStr::new_unchecked(&data[..=len]) })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { from_raw_parts(self.ptr.cast(), self.len) }
+    }
+}
+
+impl Drop for ExitData {
+    fn drop(&mut self) {
+        if boot_services_exited() {
+            return;
+        }
+
+        let _ = unsafe { system_table().boot_services().free_pool(self.ptr.cast()) };
     }
 }
 
@@ -43,6 +227,10 @@ pub struct LoadedImage {
     load_options_size: u32,
     load_options: *const (),
     image_base: *const u8,
+    image_size: u64,
+    image_code_type: u32,
+    image_data_type: u32,
+    unload: Option<unsafe extern "efiapi" fn(&Image) -> Status>,
 }
 
 impl LoadedImage {
@@ -54,7 +242,28 @@ impl LoadedImage {
         unsafe { Path::from_ptr(self.file_path) }
     }
 
+    /// Returns the load options that was passed to this image.
+    pub fn load_options(&self) -> &[u8] {
+        match self.load_options_size {
+            0 => &[],
+            n => unsafe { from_raw_parts(self.load_options.cast(), n.try_into().unwrap()) },
+        }
+    }
+
     pub fn image_base(&self) -> *const u8 {
         self.image_base
     }
+
+    pub fn image_size(&self) -> u64 {
+        self.image_size
+    }
+}
+
+unsafe impl Protocol for LoadedImage {
+    const ID: Guid = Guid::new(
+        0x5B1B31A1,
+        0x9562,
+        0x11d2,
+        [0x8E, 0x3F, 0x00, 0xA0, 0xC9, 0x69, 0x72, 0x3B],
+    );
 }
@@ -1,49 +1,70 @@
 #![no_std]
 
+pub use self::acpi::*;
 pub use self::allocator::*;
 pub use self::boot::*;
 pub use self::console::*;
 pub use self::debug::*;
 pub use self::device::*;
+pub use self::driver::*;
+pub use self::event::*;
+pub use self::executor::*;
+pub use self::fdt::*;
 pub use self::filesystem::*;
 pub use self::guid::*;
+pub use self::handoff::*;
 pub use self::header::*;
 pub use self::image::*;
 pub use self::memory::*;
 pub use self::path::*;
 pub use self::pointer::*;
 pub use self::proto::*;
+pub use self::reset::*;
 pub use self::runtime::*;
+pub use self::smbios::*;
 pub use self::status::*;
 pub use self::string::*;
 pub use self::system::*;
 pub use self::time::*;
+pub use self::timestamp::*;
+pub use self::tpl::*;
+pub use self::watchdog::*;
 pub use zfi_macros::*;
 
 use alloc::boxed::Box;
 use core::cell::RefCell;
 use core::fmt::Write;
-use core::ptr::null;
+use core::ptr::{null, null_mut};
 
+mod acpi;
 mod allocator;
 mod boot;
 mod console;
 mod debug;
 mod device;
+mod driver;
 mod event;
+mod executor;
+mod fdt;
 mod filesystem;
 mod guid;
+mod handoff;
 mod header;
 mod image;
 mod memory;
 mod path;
 mod pointer;
 mod proto;
+mod reset;
 mod runtime;
+mod smbios;
 mod status;
 mod string;
 mod system;
 mod time;
+mod timestamp;
+mod tpl;
+mod watchdog;
 
 extern crate alloc;
 extern crate self as zfi;
@@ -82,6 +103,9 @@ pub unsafe fn init(
     if let Some(f) = debug_writer {
         DEBUG_WRITER = Some(RefCell::new(f()));
     }
+
+    // Don't report the allocations of the debug writer as leaks.
+    set_leak_baseline();
 }
 
 /// Returns `im` that was passed to [`init()`].
@@ -95,3 +119,65 @@ pub fn system_table() -> &'static SystemTable {
     // SAFETY: This is safe because the only place that write ST is our init function.
     unsafe { &*ST }
 }
+
+/// Terminates the current image with `status` and optional `data` as the exit data.
+///
+/// All states owned by ZFI (e.g. the debug writer) will be dropped before terminating the image.
+/// Any values on the stack will not be dropped.
+///
+/// This function will never return if [`exit_boot_services()`] has been called.
+pub fn exit(status: Status, data: Option<&EfiStr>) -> ! {
+    // There is nothing we can do once the boot services has been terminated.
+    if boot_services_exited() {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    let bs = system_table().boot_services();
+
+    // Copy the exit data to the pool since the firmware will free it.
+    let (ptr, len) = match data {
+        Some(v) => {
+            let v: &[u8] = v.as_ref();
+
+            match bs.allocate_pool(MemoryType::LoaderData, v.len()) {
+                Ok(p) => {
+                    unsafe { p.copy_from_nonoverlapping(v.as_ptr(), v.len()) };
+                    (p.cast(), v.len())
+                }
+                Err(_) => (null_mut(), 0),
+            }
+        }
+        None => (null_mut(), 0),
+    };
+
+    // Drop our states.
+    unsafe { fini() };
+
+    // Exit() should never return for the current image.
+    let _ = unsafe { bs.exit(current_image(), status, ptr, len) };
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Drops all states owned by ZFI.
+///
+/// # Safety
+/// No any ZFI states can be used after this call.
+unsafe fn fini() {
+    report_leaks();
+
+    // We can't drop the debug writer if it is currently in use (e.g. panic while writing the log).
+    // The writer must be removed from DEBUG_WRITER before dropping so its destructor can't see
+    // itself.
+    #[allow(static_mut_refs)]
+    if let Some(w) = DEBUG_WRITER.as_ref() {
+        if w.try_borrow_mut().is_ok() {
+            let w = DEBUG_WRITER.take();
+            drop(w);
+        }
+    }
+}
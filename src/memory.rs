@@ -1,5 +1,10 @@
-use crate::{system_table, AllocateType, MemoryDescriptor, MemoryType, Status};
+use crate::{
+    boot_services_exited, system_table, AllocateType, MemoryDescriptor, MemoryType, Status,
+};
+use alloc::vec;
 use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::mem::{align_of, forget, size_of, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 
 /// Page size of the system, in bytes.
@@ -27,10 +32,391 @@ pub fn allocate_pages(
 
 /// Just a shortcut to [`super::BootServices::get_memory_map()`]. Do not discard the returned map if
 /// you want a key to use with [`super::BootServices::exit_boot_services()`].
-pub fn get_memory_map() -> Result<(Vec<MemoryDescriptor>, usize), Status> {
+pub fn get_memory_map() -> Result<MemoryMap, Status> {
     system_table().boot_services().get_memory_map()
 }
 
+/// A memory map that was returned from [`super::BootServices::get_memory_map()`].
+///
+/// The size of each descriptor is reported by the firmware, which can be larger than
+/// [`MemoryDescriptor`].
+pub struct MemoryMap {
+    buf: Vec<u64>,         // Use u64 to make it 8-byte aligned.
+    pub(crate) len: usize, // In bytes.
+    pub(crate) key: usize,
+    pub(crate) descriptor_size: usize,
+    pub(crate) descriptor_version: u32,
+}
+
+impl MemoryMap {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            buf: vec![0; capacity.div_ceil(8)],
+            len: 0,
+            key: 0,
+            descriptor_size: 0,
+            descriptor_version: 0,
+        }
+    }
+
+    /// Returns the key of this map to use with [`super::BootServices::exit_boot_services()`].
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Returns the size of each descriptor in bytes.
+    pub fn descriptor_size(&self) -> usize {
+        self.descriptor_size
+    }
+
+    pub fn descriptor_version(&self) -> u32 {
+        self.descriptor_version
+    }
+
+    /// Returns number of descriptors in this map.
+    pub fn len(&self) -> usize {
+        match self.descriptor_size {
+            v if v < size_of::<MemoryDescriptor>() => 0,
+            v => self.len / v,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<MemoryDescriptor> {
+        if index >= self.len() {
+            return None;
+        }
+
+        unsafe {
+            let ptr = self.buf.as_ptr().cast::<u8>();
+            let ptr = ptr.add(index * self.descriptor_size);
+
+            Some(ptr.cast::<MemoryDescriptor>().read_unaligned())
+        }
+    }
+
+    pub fn iter(&self) -> MemoryMapIter<'_> {
+        MemoryMapIter { map: self, next: 0 }
+    }
+
+    /// Returns the descriptors sorted by the physical address with the adjacent descriptors that
+    /// have the same type and attributes merged.
+    pub fn coalesced(&self) -> Vec<MemoryDescriptor> {
+        let mut list: Vec<MemoryDescriptor> = self.iter().filter(|d| !d.is_empty()).collect();
+
+        list.sort_unstable_by_key(|d| d.physical_start);
+        list.dedup_by(|next, prev| {
+            if next.ty == prev.ty
+                && next.attribute == prev.attribute
+                && prev.physical_start + prev.len() == next.physical_start
+            {
+                prev.number_of_pages += next.number_of_pages;
+                true
+            } else {
+                false
+            }
+        });
+
+        list
+    }
+
+    /// Returns a sorted list of regions with the adjacent regions of the same kind merged. See
+    /// [`MemoryDescriptor::region_kind()`] for how each descriptor is classified.
+    pub fn regions(&self) -> Vec<MemoryRegion> {
+        let mut list: Vec<MemoryRegion> = self
+            .iter()
+            .filter(|d| !d.is_empty())
+            .map(|d| MemoryRegion {
+                start: d.physical_start,
+                len: d.len(),
+                kind: d.region_kind(),
+            })
+            .collect();
+
+        list.sort_unstable_by_key(|r| r.start);
+        list.dedup_by(|next, prev| {
+            if next.kind == prev.kind && prev.end() == next.start {
+                prev.len += next.len;
+                true
+            } else {
+                false
+            }
+        });
+
+        list
+    }
+
+    /// Returns the total size in bytes of the memory that can be used by the OS after
+    /// `EFI_BOOT_SERVICES.ExitBootServices()`.
+    pub fn usable_size(&self) -> u64 {
+        self.iter()
+            .filter(|d| d.is_usable_after_exit())
+            .map(|d| d.len())
+            .sum()
+    }
+
+    /// Returns the largest [`MemoryType::ConventionalMemory`] region that is below `limit`. The
+    /// region that crosses `limit` will be truncated.
+    pub fn largest_free_below(&self, limit: u64) -> Option<MemoryRegion> {
+        self.coalesced()
+            .into_iter()
+            .filter(|d| d.ty() == Some(MemoryType::ConventionalMemory))
+            .filter(|d| d.physical_start < limit)
+            .map(|d| MemoryRegion {
+                start: d.physical_start,
+                len: d.len().min(limit - d.physical_start),
+                kind: RegionKind::Usable,
+            })
+            .max_by_key(|r| r.len)
+    }
+
+    /// Returns the E820 table for this map.
+    pub fn to_e820(&self) -> Vec<E820Entry> {
+        self.regions()
+            .into_iter()
+            .map(|r| E820Entry {
+                addr: r.start,
+                size: r.len,
+                ty: match r.kind {
+                    RegionKind::Usable => E820Entry::RAM,
+                    RegionKind::Reserved => E820Entry::RESERVED,
+                    RegionKind::AcpiReclaimable => E820Entry::ACPI,
+                    RegionKind::AcpiNvs => E820Entry::NVS,
+                    RegionKind::Unusable => E820Entry::UNUSABLE,
+                    RegionKind::Persistent => E820Entry::PMEM,
+                },
+            })
+            .collect()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.buf.len() * 8
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buf.as_mut_ptr().cast()
+    }
+}
+
+impl<'a> IntoIterator for &'a MemoryMap {
+    type Item = MemoryDescriptor;
+    type IntoIter = MemoryMapIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the descriptors in [`MemoryMap`].
+pub struct MemoryMapIter<'a> {
+    map: &'a MemoryMap,
+    next: usize,
+}
+
+impl Iterator for MemoryMapIter<'_> {
+    type Item = MemoryDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let d = self.map.get(self.next)?;
+        self.next += 1;
+        Some(d)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.map.len() - self.next;
+
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for MemoryMapIter<'_> {}
+
+/// A physical memory region that was classified by [`MemoryDescriptor::region_kind()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion {
+    start: u64,
+    len: u64,
+    kind: RegionKind,
+}
+
+impl MemoryRegion {
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the size of this region in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the address after the last byte of this region.
+    pub fn end(&self) -> u64 {
+        self.start + self.len
+    }
+
+    pub fn kind(&self) -> RegionKind {
+        self.kind
+    }
+}
+
+/// How the memory region can be used after `EFI_BOOT_SERVICES.ExitBootServices()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    /// Free to use by the OS.
+    Usable,
+
+    /// Must be preserved by the OS.
+    Reserved,
+
+    /// Can be used by the OS once the ACPI tables was consumed.
+    AcpiReclaimable,
+
+    /// Must be preserved by the OS, including across the sleep states.
+    AcpiNvs,
+
+    /// Contains errors.
+    Unusable,
+
+    /// Persistent memory.
+    Persistent,
+}
+
+/// An entry of E820 table, which has the same layout as the Linux boot protocol.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct E820Entry {
+    addr: u64,
+    size: u64,
+    ty: u32,
+}
+
+impl E820Entry {
+    pub const RAM: u32 = 1;
+    pub const RESERVED: u32 = 2;
+    pub const ACPI: u32 = 3;
+    pub const NVS: u32 = 4;
+    pub const UNUSABLE: u32 = 5;
+    pub const PMEM: u32 = 7;
+
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn ty(&self) -> u32 {
+        self.ty
+    }
+}
+
+/// An allocator to allocate memory pages with a specific memory type, alignment and location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageAllocator {
+    ty: MemoryType,
+    limit: PageLimit,
+}
+
+impl PageAllocator {
+    pub const fn new(ty: MemoryType, limit: PageLimit) -> Self {
+        Self { ty, limit }
+    }
+
+    pub fn memory_type(&self) -> MemoryType {
+        self.ty
+    }
+
+    pub fn limit(&self) -> PageLimit {
+        self.limit
+    }
+
+    /// Allocates the pages that fit `layout`. The alignment of `layout` can be any power of two,
+    /// including the value that is larger than [`PAGE_SIZE`].
+    ///
+    /// With [`PageLimit::Address`] the address must be aligned to `layout`, otherwise this will
+    /// fail with [`Status::INVALID_PARAMETER`].
+    pub fn allocate(&self, layout: Layout) -> Result<Pages, Status> {
+        let bs = system_table().boot_services();
+        let align = layout.align();
+        let pages = page_count(layout.size());
+
+        if pages == 0 {
+            return Err(Status::INVALID_PARAMETER);
+        }
+
+        // Allocate without any slack if the alignment is within a page.
+        let (at, addr) = match self.limit {
+            PageLimit::Any => (AllocateType::AnyPages, 0),
+            PageLimit::MaxAddress(v) => (AllocateType::MaxAddress, v),
+            PageLimit::Address(v) => {
+                return match v % align as u64 {
+                    0 => bs.allocate_pages(AllocateType::Address, self.ty, pages, v),
+                    _ => Err(Status::INVALID_PARAMETER),
+                };
+            }
+        };
+
+        if align <= PAGE_SIZE {
+            return bs.allocate_pages(at, self.ty, pages, addr);
+        }
+
+        // Allocate with a spare room for adjusting the alignment.
+        let slack = align / PAGE_SIZE - 1;
+        let mem = bs
+            .allocate_pages(at, self.ty, pages + slack, addr)?
+            .leak()
+            .as_mut_ptr();
+
+        // Free the unused pages before and after the aligned pages. There is nothing we can do if
+        // this failed.
+        let head = ((mem as usize).next_multiple_of(align) - mem as usize) / PAGE_SIZE;
+        let tail = slack - head;
+        let aligned = unsafe { mem.add(head * PAGE_SIZE) };
+
+        unsafe {
+            if head != 0 {
+                let _ = bs.free_pages(mem, head);
+            }
+
+            if tail != 0 {
+                let _ = bs.free_pages(aligned.add(pages * PAGE_SIZE), tail);
+            }
+        }
+
+        Ok(unsafe { Pages::new(aligned, pages * PAGE_SIZE) })
+    }
+
+    /// Same as [`Self::allocate()`] but the pages are filled with zeroes.
+    pub fn allocate_zeroed(&self, layout: Layout) -> Result<Pages, Status> {
+        let mut pages = self.allocate(layout)?;
+
+        pages.fill(0);
+
+        Ok(pages)
+    }
+}
+
+/// Location of the pages to allocate with [`PageAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageLimit {
+    /// Anywhere.
+    Any,
+
+    /// The end of the pages must be at or below the specified address.
+    MaxAddress(u64),
+
+    /// The pages must start at the specified address.
+    Address(u64),
+}
+
 /// Encapsulate a pointer to one or more memory pages.
 pub struct Pages {
     ptr: *mut u8,
@@ -47,10 +433,54 @@ impl Pages {
     pub fn addr(&self) -> usize {
         self.ptr as _
     }
+
+    /// Consumes the pages without freeing it (e.g. to hand it to the OS kernel).
+    pub fn leak(self) -> &'static mut [u8] {
+        let pages = unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) };
+
+        forget(self);
+
+        pages
+    }
+
+    /// Returns the pages as a slice of `T`. Any remaining bytes that are not fit in `T` will be
+    /// excluded.
+    ///
+    /// # Panics
+    /// If the pages are not aligned for `T`.
+    pub fn as_uninit_slice<T>(&self) -> &[MaybeUninit<T>] {
+        let len = self.uninit_len::<T>();
+
+        unsafe { core::slice::from_raw_parts(self.ptr.cast(), len) }
+    }
+
+    /// Returns the pages as a mutable slice of `T`. Any remaining bytes that are not fit in `T` will
+    /// be excluded.
+    ///
+    /// # Panics
+    /// If the pages are not aligned for `T`.
+    pub fn as_uninit_slice_mut<T>(&mut self) -> &mut [MaybeUninit<T>] {
+        let len = self.uninit_len::<T>();
+
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.cast(), len) }
+    }
+
+    fn uninit_len<T>(&self) -> usize {
+        assert_eq!(self.ptr as usize % align_of::<T>(), 0);
+
+        match size_of::<T>() {
+            0 => 0,
+            v => self.len / v,
+        }
+    }
 }
 
 impl Drop for Pages {
     fn drop(&mut self) {
+        if boot_services_exited() {
+            return;
+        }
+
         unsafe {
             system_table()
                 .boot_services()
@@ -73,3 +503,88 @@ impl DerefMut for Pages {
         unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryAttributes;
+
+    #[test]
+    fn analysis() {
+        let map = map(&[
+            (MemoryType::ConventionalMemory, 0x100000, 0x100),
+            (MemoryType::LoaderData, 0x1000, 0x10),
+            (MemoryType::BootServicesData, 0x11000, 0x20),
+            (MemoryType::ConventionalMemory, 0x200000, 0x100),
+            (MemoryType::AcpiReclaimMemory, 0x300000, 0x10),
+            (MemoryType::MemoryMappedIo, 0xfec00000, 1),
+            (MemoryType::ConventionalMemory, 0x310000, 0x1000),
+        ]);
+
+        // Check coalesced.
+        let list = map.coalesced();
+
+        assert_eq!(list.len(), 6);
+        assert_eq!(list[0].physical_start(), 0x1000);
+        assert_eq!(list[2].physical_start(), 0x100000);
+        assert_eq!(list[2].page_count(), 0x200);
+
+        // Check regions.
+        let regions = map.regions();
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions[0].start(), 0x1000);
+        assert_eq!(regions[0].len(), 0x30000);
+        assert_eq!(regions[0].kind(), RegionKind::Usable);
+        assert_eq!(regions[1].end(), 0x300000);
+        assert_eq!(regions[2].kind(), RegionKind::AcpiReclaimable);
+        assert_eq!(regions[4].kind(), RegionKind::Reserved);
+
+        // Check usable size and the largest free region.
+        let free = map.largest_free_below(0x180000).unwrap();
+
+        assert_eq!(map.usable_size(), (0x10 + 0x20 + 0x200 + 0x1000) * 0x1000);
+        assert_eq!(free.start(), 0x100000);
+        assert_eq!(free.len(), 0x80000);
+        assert_eq!(map.largest_free_below(0x400000).unwrap().len(), 0x200000);
+        assert_eq!(map.largest_free_below(u64::MAX).unwrap().start(), 0x310000);
+        assert!(map.largest_free_below(0x1000).is_none());
+
+        // Check E820.
+        let e820 = map.to_e820();
+
+        assert_eq!(e820.len(), 5);
+        assert_eq!(e820[0].ty(), E820Entry::RAM);
+        assert_eq!(e820[2].ty(), E820Entry::ACPI);
+        assert_eq!(e820[4].addr(), 0xfec00000);
+        assert_eq!(e820[4].ty(), E820Entry::RESERVED);
+    }
+
+    fn map(list: &[(MemoryType, u64, u64)]) -> MemoryMap {
+        // Use a larger descriptor to make sure we honor the descriptor size.
+        let dsize = size_of::<MemoryDescriptor>() + 8;
+        let mut map = MemoryMap::new(dsize * list.len());
+
+        for (i, &(ty, start, pages)) in list.iter().enumerate() {
+            let d = MemoryDescriptor {
+                ty: ty as u32,
+                physical_start: start,
+                virtual_start: 0,
+                number_of_pages: pages,
+                attribute: MemoryAttributes::WB.bits(),
+            };
+
+            unsafe {
+                map.as_mut_ptr()
+                    .add(dsize * i)
+                    .cast::<MemoryDescriptor>()
+                    .write_unaligned(d)
+            };
+        }
+
+        map.len = dsize * list.len();
+        map.descriptor_size = dsize;
+        map.descriptor_version = 1;
+        map
+    }
+}
@@ -1,4 +1,4 @@
-use crate::EfiStr;
+use crate::{EfiStr, Guid};
 use alloc::borrow::{Cow, ToOwned};
 use core::borrow::Borrow;
 use core::fmt::Formatter;
@@ -12,6 +12,14 @@ use core::slice::from_raw_parts;
 pub struct Path([u8]);
 
 impl Path {
+    /// GUID of `EFI_DEVICE_PATH_PROTOCOL`.
+    pub const ID: Guid = Guid::new(
+        0x09576e91,
+        0x6d3f,
+        0x11d2,
+        [0x8e, 0x39, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
+    );
+
     pub const EMPTY: &'static Path = unsafe { Self::new_unchecked(&[0x7F, 0xFF, 0x04, 0x00]) };
 
     /// # Safety
@@ -1,3 +1,4 @@
+use crate::boot_services_exited;
 use alloc::boxed::Box;
 use core::ops::{Deref, DerefMut};
 
@@ -20,6 +21,11 @@ impl<T> Owned<T> {
 
 impl<T> Drop for Owned<T> {
     fn drop(&mut self) {
+        // The destructor most likely need the boot services.
+        if boot_services_exited() {
+            return;
+        }
+
         match self.dtor.take().unwrap() {
             Dtor::Function(f) => f(self.ptr),
             Dtor::Closure(f) => f(self.ptr),
@@ -1,4 +1,11 @@
-use crate::{system_table, Guid, OpenProtocolAttributes, Status};
+use crate::{
+    boot_services_exited, system_table, Device, Event, EventType, Guid, Image, LocateSearch,
+    OpenProtocolAttributes, Owned, Path, PathBuf, Status, Tpl,
+};
+use alloc::boxed::Box;
+use core::mem::forget;
+use core::ops::Deref;
+use core::ptr::null;
 
 /// Invokes `EFI_BOOT_SERVICES.OpenProtocol`.
 ///
@@ -25,3 +32,278 @@ pub unsafe fn open_protocol(
         .boot_services()
         .open_protocol(handle, proto, agent, controller, attrs)
 }
+
+/// Returns the first interface of `P` that was installed on any handle.
+pub fn locate_protocol<P: Protocol>() -> Option<&'static P> {
+    system_table()
+        .boot_services()
+        .locate_protocol(&P::ID)
+        .map(|v| unsafe { &*v.cast() })
+}
+
+/// A type that represents a protocol interface.
+///
+/// # Safety
+/// The type must have the same layout as the protocol interface that identified by [`Self::ID`].
+pub unsafe trait Protocol {
+    /// GUID of the protocol.
+    const ID: Guid;
+}
+
+/// An opened protocol interface, which will be closed when dropped.
+pub struct OpenedProtocol<'a, P: Protocol> {
+    handle: &'a Device,
+    interface: &'a P,
+    agent: &'a Image,
+    controller: Option<&'a Device>,
+}
+
+impl<'a, P: Protocol> OpenedProtocol<'a, P> {
+    pub(crate) fn new(
+        handle: &'a Device,
+        agent: &'a Image,
+        controller: Option<&'a Device>,
+        attrs: OpenProtocolAttributes,
+    ) -> Result<Self, Status> {
+        // There is no interface to return with TEST_PROTOCOL.
+        if attrs.contains(OpenProtocolAttributes::TEST_PROTOCOL) {
+            return Err(Status::INVALID_PARAMETER);
+        }
+
+        // Open the protocol.
+        let interface = unsafe {
+            system_table().boot_services().open_protocol(
+                handle as *const Device as *const (),
+                &P::ID,
+                agent as *const Image as *const (),
+                controller.map_or(null(), |v| v as *const Device as *const ()),
+                attrs,
+            )?
+        };
+
+        Ok(Self {
+            handle,
+            interface: unsafe { &*(interface as *const P) },
+            agent,
+            controller,
+        })
+    }
+
+    /// Returns the handle that this protocol was opened from.
+    pub fn handle(&self) -> &'a Device {
+        self.handle
+    }
+}
+
+impl<P: Protocol> Drop for OpenedProtocol<'_, P> {
+    fn drop(&mut self) {
+        if boot_services_exited() {
+            return;
+        }
+
+        // The open information may already removed by the firmware (e.g. when the protocol was
+        // reinstalled) so we don't care about the result here.
+        let _ = unsafe {
+            system_table().boot_services().close_protocol(
+                self.handle,
+                &P::ID,
+                self.agent,
+                self.controller,
+            )
+        };
+    }
+}
+
+impl<P: Protocol> Deref for OpenedProtocol<'_, P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        self.interface
+    }
+}
+
+impl<P: Protocol> AsRef<P> for OpenedProtocol<'_, P> {
+    fn as_ref(&self) -> &P {
+        self.interface
+    }
+}
+
+/// A protocol interface that was installed by this image, which will be uninstalled when dropped.
+///
+/// The interface will be leaked if it cannot be uninstalled (e.g. it is currently opened by a
+/// driver).
+pub struct InstalledProtocol<P: Protocol> {
+    handle: &'static Device,
+    interface: Option<Box<P>>,
+    path: Option<PathBuf>,
+}
+
+impl<P: Protocol> InstalledProtocol<P> {
+    /// Installs `interface` on `handle` or a new handle if `handle` is [`None`].
+    pub fn install(handle: Option<&'static Device>, interface: Box<P>) -> Result<Self, Status> {
+        let handle = unsafe {
+            system_table().boot_services().install_protocol_interface(
+                handle,
+                &P::ID,
+                interface.as_ref() as *const P as *const (),
+            )?
+        };
+
+        Ok(Self {
+            handle,
+            interface: Some(interface),
+            path: None,
+        })
+    }
+
+    /// Installs `interface` together with `path` on a new handle.
+    ///
+    /// This will fail with [`Status::ALREADY_STARTED`] if there is already a handle with the same
+    /// device path.
+    pub fn install_with_path(path: PathBuf, interface: Box<P>) -> Result<Self, Status> {
+        let interfaces = [
+            (&Path::ID, path.as_bytes().as_ptr() as *const ()),
+            (&P::ID, interface.as_ref() as *const P as *const ()),
+        ];
+        let handle = unsafe {
+            system_table()
+                .boot_services()
+                .install_multiple_protocol_interfaces(None, &interfaces)?
+        };
+
+        Ok(Self {
+            handle,
+            interface: Some(interface),
+            path: Some(path),
+        })
+    }
+
+    /// Returns the handle that the interface was installed on.
+    pub fn handle(&self) -> &'static Device {
+        self.handle
+    }
+
+    /// Replaces the installed interface with `interface`. Returns the previous interface.
+    pub fn reinstall(&mut self, interface: Box<P>) -> Result<Box<P>, Status> {
+        let old = self.interface.as_deref().unwrap() as *const P as *const ();
+        let new = interface.as_ref() as *const P as *const ();
+
+        unsafe {
+            system_table()
+                .boot_services()
+                .reinstall_protocol_interface(self.handle, &P::ID, old, new)?
+        };
+
+        Ok(self.interface.replace(interface).unwrap())
+    }
+
+    /// Uninstalls the interface and returns it.
+    ///
+    /// The interface will be leaked if this method fails.
+    pub fn uninstall(mut self) -> Result<Box<P>, Status> {
+        self.remove()?;
+
+        Ok(self.interface.take().unwrap())
+    }
+
+    fn remove(&mut self) -> Result<(), Status> {
+        let bs = system_table().boot_services();
+        let interface = self.interface.as_deref().unwrap() as *const P as *const ();
+        let result = match &self.path {
+            Some(path) => unsafe {
+                bs.uninstall_multiple_protocol_interfaces(
+                    self.handle,
+                    &[
+                        (&Path::ID, path.as_bytes().as_ptr() as *const ()),
+                        (&P::ID, interface),
+                    ],
+                )
+            },
+            None => unsafe { bs.uninstall_protocol_interface(self.handle, &P::ID, interface) },
+        };
+
+        // Leak the interface if it cannot be uninstalled since it still can be accessed.
+        if result.is_err() {
+            forget(self.interface.take());
+            forget(self.path.take());
+        }
+
+        result
+    }
+}
+
+impl<P: Protocol> Drop for InstalledProtocol<P> {
+    fn drop(&mut self) {
+        if boot_services_exited() {
+            forget(self.interface.take());
+            forget(self.path.take());
+        } else if self.interface.is_some() {
+            let _ = self.remove();
+        }
+    }
+}
+
+impl<P: Protocol> Deref for InstalledProtocol<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        self.interface.as_deref().unwrap()
+    }
+}
+
+/// A registration to get notified when a protocol interface is installed.
+///
+/// Use [`ProtocolNotify::event()`] to wait for the notification and [`ProtocolNotify::handles()`]
+/// to get the handles that have a new interface installed.
+pub struct ProtocolNotify {
+    event: Owned<Event>,
+    key: *const (),
+}
+
+impl ProtocolNotify {
+    /// Registers for the notification of `P`.
+    pub fn new<P: Protocol>() -> Result<Self, Status> {
+        Self::with_guid(&P::ID)
+    }
+
+    /// Registers for the notification of `proto`.
+    pub fn with_guid(proto: &Guid) -> Result<Self, Status> {
+        let bs = system_table().boot_services();
+        let event = bs.create_event(EventType::empty(), Tpl::CALLBACK, None)?;
+        let key = bs.register_protocol_notify(proto, &event)?;
+
+        Ok(Self { event, key })
+    }
+
+    /// Returns the event that will be signaled when a new interface is installed.
+    pub fn event(&self) -> &Event {
+        &self.event
+    }
+
+    /// Returns an iterator over the handles that have a new interface installed since the last
+    /// call.
+    pub fn handles(&self) -> NewHandles<'_> {
+        NewHandles(self)
+    }
+
+    pub(crate) fn key(&self) -> *const () {
+        self.key
+    }
+}
+
+/// An iterator over the handles that have a new interface installed.
+///
+/// This iterator will stop on the first error.
+pub struct NewHandles<'a>(&'a ProtocolNotify);
+
+impl Iterator for NewHandles<'_> {
+    type Item = &'static Device;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        system_table()
+            .boot_services()
+            .locate_handle(LocateSearch::ByRegisterNotify(self.0))
+            .ok()
+            .and_then(|v| v.first().copied())
+    }
+}
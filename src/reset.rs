@@ -0,0 +1,51 @@
+use crate::{boot_services_exited, fini, system_table, EfiStr, Guid, ResetType, Status};
+use alloc::vec::Vec;
+use core::mem::size_of;
+use core::slice::from_raw_parts;
+
+/// Resets the entire platform.
+///
+/// `description` will be passed to the firmware as a part of the reset data. All states owned by
+/// ZFI (e.g. the debug writer) will be dropped before resetting the platform.
+///
+/// # Panics
+/// If `ty` is [`ResetType::PlatformSpecific`]. Use [`reset_platform_specific()`] instead.
+pub fn reset(ty: ResetType, status: Status, description: Option<&EfiStr>) -> ! {
+    assert_ne!(
+        ty,
+        ResetType::PlatformSpecific,
+        "use reset_platform_specific() for a platform-specific reset"
+    );
+
+    reset_system(ty, status, description.map(AsRef::<[u8]>::as_ref))
+}
+
+/// Resets the entire platform with a platform-specific reset that is identified by `guid`.
+///
+/// The firmware will fallback to a cold reset if it does not recognize `guid`. See [`reset()`] for
+/// more details.
+pub fn reset_platform_specific(guid: &Guid, status: Status, description: Option<&EfiStr>) -> ! {
+    let description: &[u8] = description.unwrap_or(EfiStr::EMPTY).as_ref();
+    let guid = unsafe { from_raw_parts((guid as *const Guid).cast::<u8>(), size_of::<Guid>()) };
+    let mut data = Vec::with_capacity(description.len() + guid.len());
+
+    // The reset data is a NUL-terminated string followed by the GUID.
+    data.extend_from_slice(description);
+    data.extend_from_slice(guid);
+
+    reset_system(ResetType::PlatformSpecific, status, Some(&data))
+}
+
+fn reset_system(ty: ResetType, status: Status, data: Option<&[u8]>) -> ! {
+    // Drop our states so the debug log will be flushed. The debug writer was already leaked if the
+    // boot services has been terminated.
+    if !boot_services_exited() {
+        unsafe { fini() };
+    }
+
+    unsafe {
+        system_table()
+            .runtime_services()
+            .reset_system(ty, status, data)
+    }
+}
@@ -1,3 +1,305 @@
+use crate::{
+    EfiStr, EfiString, Guid, Status, TableHeader, TableRevision, Time, TimeCapabilities, WakeupTime,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use bitflags::bitflags;
+use core::mem::MaybeUninit;
+use core::ptr::null;
+
+/// Vendor GUID of the variables that defined by the UEFI specification (e.g. `BootOrder`).
+pub const GLOBAL_VARIABLE: Guid = Guid::new(
+    0x8be4df61,
+    0x93ca,
+    0x11d2,
+    [0xaa, 0x0d, 0x00, 0xe0, 0x98, 0x03, 0x2b, 0x8c],
+);
+
 /// Represents an `EFI_RUNTIME_SERVICES`.
 #[repr(C)]
-pub struct RuntimeServices {}
+pub struct RuntimeServices {
+    hdr: TableHeader,
+    get_time: unsafe extern "efiapi" fn(*mut Time, *mut TimeCapabilities) -> Status,
+    set_time: unsafe extern "efiapi" fn(*const Time) -> Status,
+    get_wakeup_time: unsafe extern "efiapi" fn(*mut u8, *mut u8, *mut Time) -> Status,
+    set_wakeup_time: unsafe extern "efiapi" fn(u8, *const Time) -> Status,
+    set_virtual_address_map: fn(),
+    convert_pointer: fn(),
+    get_variable:
+        unsafe extern "efiapi" fn(*const u16, *const Guid, *mut u32, *mut usize, *mut u8) -> Status,
+    get_next_variable_name: unsafe extern "efiapi" fn(*mut usize, *mut u16, *mut Guid) -> Status,
+    set_variable:
+        unsafe extern "efiapi" fn(*const u16, *const Guid, u32, usize, *const u8) -> Status,
+    get_next_high_monotonic_count: fn(),
+    reset_system: unsafe extern "efiapi" fn(ResetType, Status, usize, *const u8) -> !,
+    update_capsule: fn(),
+    query_capsule_capabilities: fn(),
+    query_variable_info: unsafe extern "efiapi" fn(u32, *mut u64, *mut u64, *mut u64) -> Status,
+}
+
+impl RuntimeServices {
+    pub fn hdr(&self) -> &TableHeader {
+        &self.hdr
+    }
+
+    /// Returns the current time and the capabilities of the real-time clock.
+    pub fn get_time(&self) -> Result<(Time, TimeCapabilities), Status> {
+        let mut time = Time::ZERO;
+        let mut caps = MaybeUninit::uninit();
+
+        unsafe {
+            (self.get_time)(&mut time, caps.as_mut_ptr()).err_or(())?;
+
+            Ok((time, caps.assume_init()))
+        }
+    }
+
+    /// Sets the current time of the real-time clock.
+    pub fn set_time(&self, time: &Time) -> Result<(), Status> {
+        unsafe { (self.set_time)(time).err_or(()) }
+    }
+
+    /// Returns the current setting of the wakeup alarm.
+    pub fn get_wakeup_time(&self) -> Result<WakeupTime, Status> {
+        let mut enabled = 0;
+        let mut pending = 0;
+        let mut time = Time::ZERO;
+
+        unsafe {
+            (self.get_wakeup_time)(&mut enabled, &mut pending, &mut time).err_or(WakeupTime {
+                enabled: enabled != 0,
+                pending: pending != 0,
+                time,
+            })
+        }
+    }
+
+    /// Sets the wakeup alarm to `time` or disable it if `time` is [`None`].
+    pub fn set_wakeup_time(&self, time: Option<&Time>) -> Result<(), Status> {
+        let (enabled, time) = match time {
+            Some(v) => (true, v as *const Time),
+            None => (false, null()),
+        };
+
+        unsafe { (self.set_wakeup_time)(enabled.into(), time).err_or(()) }
+    }
+
+    /// Returns the attributes and the data of the variable.
+    pub fn get_variable(
+        &self,
+        name: &EfiStr,
+        vendor: &Guid,
+    ) -> Result<(VariableAttributes, Vec<u8>), Status> {
+        // Try until the buffer is enough.
+        let mut data = vec![0; 256];
+        let mut attrs = 0;
+
+        loop {
+            let mut len = data.len();
+            let status = unsafe {
+                (self.get_variable)(
+                    name.as_ptr(),
+                    vendor,
+                    &mut attrs,
+                    &mut len,
+                    data.as_mut_ptr(),
+                )
+            };
+
+            match status {
+                Status::SUCCESS => {
+                    data.truncate(len);
+                    break Ok((VariableAttributes::from_bits_retain(attrs), data));
+                }
+                Status::BUFFER_TOO_SMALL => data.resize(len, 0),
+                v => break Err(v),
+            }
+        }
+    }
+
+    /// Creates, updates or deletes the variable. An empty `data` will delete the variable unless
+    /// `attrs` contains [`VariableAttributes::APPEND_WRITE`].
+    pub fn set_variable(
+        &self,
+        name: &EfiStr,
+        vendor: &Guid,
+        attrs: VariableAttributes,
+        data: &[u8],
+    ) -> Result<(), Status> {
+        unsafe {
+            (self.set_variable)(
+                name.as_ptr(),
+                vendor,
+                attrs.bits(),
+                data.len(),
+                data.as_ptr(),
+            )
+            .err_or(())
+        }
+    }
+
+    /// Deletes the variable.
+    pub fn delete_variable(&self, name: &EfiStr, vendor: &Guid) -> Result<(), Status> {
+        self.set_variable(name, vendor, VariableAttributes::empty(), &[])
+    }
+
+    /// Returns an iterator over the name and the vendor GUID of all variables.
+    pub fn variable_names(&self) -> VariableNames<'_> {
+        VariableNames {
+            rt: self,
+            name: vec![0; 64],
+            vendor: Guid::new(0, 0, 0, [0; 8]),
+            done: false,
+        }
+    }
+
+    /// Resets the entire platform. Use [`crate::reset()`] or [`crate::reset_platform_specific()`]
+    /// for a safe version.
+    ///
+    /// # Safety
+    /// `data` must start with a NUL-terminated string. With [`ResetType::PlatformSpecific`] the
+    /// string must be followed by a GUID that identify the type of reset.
+    pub unsafe fn reset_system(&self, ty: ResetType, status: Status, data: Option<&[u8]>) -> ! {
+        let (len, data) = match data {
+            Some(v) => (v.len(), v.as_ptr()),
+            None => (0, null()),
+        };
+
+        (self.reset_system)(ty, status, len, data)
+    }
+
+    /// Returns the information about the storage of the variables with `attrs`.
+    ///
+    /// This requires UEFI 2.0 or later. [`Status::UNSUPPORTED`] will be returned if the firmware
+    /// is older than that.
+    pub fn query_variable_info(
+        &self,
+        attrs: VariableAttributes,
+    ) -> Result<VariableStorageInfo, Status> {
+        let mut info = VariableStorageInfo {
+            max_storage: 0,
+            remaining_storage: 0,
+            max_variable: 0,
+        };
+
+        if self.hdr.revision() < TableRevision::new(2, 0) {
+            return Err(Status::UNSUPPORTED);
+        }
+
+        unsafe {
+            (self.query_variable_info)(
+                attrs.bits(),
+                &mut info.max_storage,
+                &mut info.remaining_storage,
+                &mut info.max_variable,
+            )
+            .err_or(info)
+        }
+    }
+}
+
+/// Represents an `EFI_RESET_TYPE`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetType {
+    Cold,
+    Warm,
+    Shutdown,
+    PlatformSpecific,
+}
+
+bitflags! {
+    /// Attributes of a variable.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VariableAttributes: u32 {
+        const NON_VOLATILE = 0x00000001;
+        const BOOTSERVICE_ACCESS = 0x00000002;
+        const RUNTIME_ACCESS = 0x00000004;
+        const HARDWARE_ERROR_RECORD = 0x00000008;
+        const AUTHENTICATED_WRITE_ACCESS = 0x00000010;
+        const TIME_BASED_AUTHENTICATED_WRITE_ACCESS = 0x00000020;
+        const APPEND_WRITE = 0x00000040;
+        const ENHANCED_AUTHENTICATED_ACCESS = 0x00000080;
+    }
+}
+
+/// An iterator over the name and the vendor GUID of all variables.
+///
+/// This iterator will stop on the first error.
+pub struct VariableNames<'a> {
+    rt: &'a RuntimeServices,
+    name: Vec<u16>,
+    vendor: Guid,
+    done: bool,
+}
+
+impl Iterator for VariableNames<'_> {
+    type Item = Result<(EfiString, Guid), Status>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            // The buffer must contains the previous name.
+            let mut len = self.name.len() * 2;
+            let status = unsafe {
+                (self.rt.get_next_variable_name)(&mut len, self.name.as_mut_ptr(), &mut self.vendor)
+            };
+
+            match status {
+                Status::SUCCESS => break,
+                Status::BUFFER_TOO_SMALL => self.name.resize(len.div_ceil(2), 0),
+                Status::NOT_FOUND => {
+                    self.done = true;
+                    return None;
+                }
+                v => {
+                    self.done = true;
+                    return Some(Err(v));
+                }
+            }
+        }
+
+        // Copy the name.
+        let len = match self.name.iter().position(|&c| c == 0) {
+            Some(v) => v,
+            None => {
+                self.done = true;
+                return Some(Err(Status::COMPROMISED_DATA));
+            }
+        };
+
+        // SAFETY: The firmware is responsible for the content of the name.
+        let name = unsafe { EfiString::new_unchecked(self.name[..=len].to_vec()) };
+
+        Some(Ok((name, self.vendor)))
+    }
+}
+
+/// Information about the storage of the variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariableStorageInfo {
+    max_storage: u64,
+    remaining_storage: u64,
+    max_variable: u64,
+}
+
+impl VariableStorageInfo {
+    /// Returns the size of the storage for the variables.
+    pub fn max_storage(&self) -> u64 {
+        self.max_storage
+    }
+
+    /// Returns the remaining size of the storage for the variables.
+    pub fn remaining_storage(&self) -> u64 {
+        self.remaining_storage
+    }
+
+    /// Returns the maximum size of a single variable, including its name.
+    pub fn max_variable(&self) -> u64 {
+        self.max_variable
+    }
+}
@@ -0,0 +1,884 @@
+use crate::{system_table, Guid};
+use core::slice::from_raw_parts;
+use thiserror::Error;
+
+/// SMBIOS structure table.
+#[derive(Clone, Copy)]
+pub struct Smbios<'a> {
+    major: u8,
+    minor: u8,
+    count: Option<u16>,
+    table: &'a [u8],
+}
+
+impl Smbios<'static> {
+    /// GUID of the configuration table for SMBIOS 2.x entry point.
+    pub const SMBIOS_GUID: Guid = Guid::new(
+        0xeb9d2d31,
+        0x2d88,
+        0x11d3,
+        [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+    );
+
+    /// GUID of the configuration table for SMBIOS 3.x entry point.
+    pub const SMBIOS3_GUID: Guid = Guid::new(
+        0xf2fd1544,
+        0x9794,
+        0x4a2c,
+        [0x99, 0x2e, 0xe5, 0xbb, 0xcf, 0x20, 0xe3, 0x94],
+    );
+
+    /// Locates the SMBIOS table from the configuration table. SMBIOS 3.x will be preferred if the
+    /// firmware provides both versions.
+    pub fn find() -> Result<Self, SmbiosError> {
+        let st = system_table();
+        let ep = st
+            .find_config_table(&Self::SMBIOS3_GUID)
+            .or_else(|| st.find_config_table(&Self::SMBIOS_GUID))
+            .ok_or(SmbiosError::NotFound)?;
+
+        // Get the length of the entry point.
+        let ep: *const u8 = ep.cast();
+        let len = unsafe {
+            match from_raw_parts(ep, 4) {
+                b"_SM3" => *ep.add(6),
+                b"_SM_" => *ep.add(5),
+                _ => return Err(SmbiosError::InvalidAnchor),
+            }
+        };
+
+        // Parse the entry point.
+        let ep = SmbiosEntryPoint::parse(unsafe { from_raw_parts(ep, len.into()) })?;
+        let addr = usize::try_from(ep.table_address).map_err(|_| SmbiosError::InvalidLength)?;
+        let len = usize::try_from(ep.table_length).map_err(|_| SmbiosError::InvalidLength)?;
+
+        Ok(Self::new(&ep, unsafe {
+            from_raw_parts(addr as *const u8, len)
+        }))
+    }
+}
+
+impl<'a> Smbios<'a> {
+    /// Creates a new [`Smbios`] from the entry point and the structure table it is pointed to.
+    pub fn new(ep: &SmbiosEntryPoint, table: &'a [u8]) -> Self {
+        Self {
+            major: ep.major,
+            minor: ep.minor,
+            count: ep.count,
+            table,
+        }
+    }
+
+    /// Returns the major and minor version of SMBIOS.
+    pub fn version(&self) -> (u8, u8) {
+        (self.major, self.minor)
+    }
+
+    /// Returns an iterator over the structures in the table.
+    pub fn structures(&self) -> SmbiosStructures<'a> {
+        SmbiosStructures {
+            version: (self.major, self.minor),
+            data: self.table,
+            remaining: self.count,
+        }
+    }
+
+    /// Returns the first structure of `T`.
+    pub fn structure<T: SmbiosType<'a>>(&self) -> Option<T> {
+        self.structures().find_map(|s| s.cast())
+    }
+
+    /// Returns an iterator over the structures of `T`.
+    pub fn structures_of<T: SmbiosType<'a>>(&self) -> impl Iterator<Item = T> + 'a {
+        self.structures().filter_map(|s| s.cast())
+    }
+}
+
+/// SMBIOS 2.x or 3.x entry point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmbiosEntryPoint {
+    major: u8,
+    minor: u8,
+    count: Option<u16>,
+    table_address: u64,
+    table_length: u32,
+}
+
+impl SmbiosEntryPoint {
+    /// Parses the entry point from `data`, which must have the same length as the length
+    /// specified in the entry point.
+    pub fn parse(data: &[u8]) -> Result<Self, SmbiosError> {
+        if data.starts_with(b"_SM3_") {
+            Self::parse_v3(data)
+        } else if data.starts_with(b"_SM_") {
+            Self::parse_v2(data)
+        } else {
+            Err(SmbiosError::InvalidAnchor)
+        }
+    }
+
+    /// Returns the major and minor version of SMBIOS.
+    pub fn version(&self) -> (u8, u8) {
+        (self.major, self.minor)
+    }
+
+    /// Returns the physical address of the structure table.
+    pub fn table_address(&self) -> u64 {
+        self.table_address
+    }
+
+    /// Returns the length of the structure table. This will be the maximum length for SMBIOS 3.x.
+    pub fn table_length(&self) -> u32 {
+        self.table_length
+    }
+
+    fn parse_v2(data: &[u8]) -> Result<Self, SmbiosError> {
+        // Check length.
+        if data.len() < 0x1F || usize::from(data[5]) != data.len() {
+            return Err(SmbiosError::InvalidLength);
+        } else if &data[0x10..0x15] != b"_DMI_" {
+            return Err(SmbiosError::InvalidAnchor);
+        } else if !checksum(data) || !checksum(&data[0x10..0x1F]) {
+            return Err(SmbiosError::InvalidChecksum);
+        }
+
+        Ok(Self {
+            major: data[6],
+            minor: data[7],
+            count: Some(u16::from_le_bytes([data[0x1C], data[0x1D]])),
+            table_address: u32::from_le_bytes(data[0x18..0x1C].try_into().unwrap()).into(),
+            table_length: u16::from_le_bytes([data[0x16], data[0x17]]).into(),
+        })
+    }
+
+    fn parse_v3(data: &[u8]) -> Result<Self, SmbiosError> {
+        if data.len() < 0x18 || usize::from(data[6]) != data.len() {
+            return Err(SmbiosError::InvalidLength);
+        } else if !checksum(data) {
+            return Err(SmbiosError::InvalidChecksum);
+        }
+
+        Ok(Self {
+            major: data[7],
+            minor: data[8],
+            count: None,
+            table_address: u64::from_le_bytes(data[0x10..0x18].try_into().unwrap()),
+            table_length: u32::from_le_bytes(data[0xC..0x10].try_into().unwrap()),
+        })
+    }
+}
+
+/// An iterator over the structures in [`Smbios`].
+pub struct SmbiosStructures<'a> {
+    version: (u8, u8),
+    data: &'a [u8],
+    remaining: Option<u16>,
+}
+
+impl<'a> Iterator for SmbiosStructures<'a> {
+    type Item = SmbiosStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Check if we reached the maximum structures.
+        if let Some(n) = &mut self.remaining {
+            *n = n.checked_sub(1)?;
+        }
+
+        // Get formatted section.
+        let len = usize::from(*self.data.get(1)?);
+
+        if len < 4 || len > self.data.len() {
+            self.data = &[];
+            return None;
+        }
+
+        // Get string-set, which is terminated with a double NUL.
+        let end = match self.data[len..].windows(2).position(|v| v == [0, 0]) {
+            Some(v) => len + v + 2,
+            None => {
+                self.data = &[];
+                return None;
+            }
+        };
+
+        let s = SmbiosStructure {
+            version: self.version,
+            data: &self.data[..len],
+            strings: &self.data[len..end],
+        };
+
+        self.data = &self.data[end..];
+
+        // Stop on the end-of-table.
+        if s.ty() == 127 {
+            self.data = &[];
+            return None;
+        }
+
+        Some(s)
+    }
+}
+
+/// A structure in [`Smbios`].
+#[derive(Clone, Copy)]
+pub struct SmbiosStructure<'a> {
+    version: (u8, u8),
+    data: &'a [u8],
+    strings: &'a [u8],
+}
+
+impl<'a> SmbiosStructure<'a> {
+    pub fn ty(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn handle(&self) -> u16 {
+        u16::from_le_bytes([self.data[2], self.data[3]])
+    }
+
+    /// Returns the formatted section, including the header.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns a string from the string-set. `index` is one-based as specified in the formatted
+    /// section. Returns [`None`] if `index` is zero, out of range or the string is not a valid
+    /// UTF-8.
+    pub fn string(&self, index: u8) -> Option<&'a str> {
+        let index = usize::from(index).checked_sub(1)?;
+        let s = self
+            .strings
+            .split(|&b| b == 0)
+            .take_while(|s| !s.is_empty())
+            .nth(index)?;
+
+        core::str::from_utf8(s).ok()
+    }
+
+    /// Casts this structure to `T`. Returns [`None`] if the type is not matched.
+    pub fn cast<T: SmbiosType<'a>>(self) -> Option<T> {
+        if self.ty() == T::TYPE {
+            Some(T::from_structure(self))
+        } else {
+            None
+        }
+    }
+
+    fn u8(&self, off: usize) -> Option<u8> {
+        self.data.get(off).copied()
+    }
+
+    fn u16(&self, off: usize) -> Option<u16> {
+        self.data
+            .get(off..(off + 2))
+            .map(|v| u16::from_le_bytes(v.try_into().unwrap()))
+    }
+
+    fn u32(&self, off: usize) -> Option<u32> {
+        self.data
+            .get(off..(off + 4))
+            .map(|v| u32::from_le_bytes(v.try_into().unwrap()))
+    }
+
+    fn u64(&self, off: usize) -> Option<u64> {
+        self.data
+            .get(off..(off + 8))
+            .map(|v| u64::from_le_bytes(v.try_into().unwrap()))
+    }
+
+    fn str(&self, off: usize) -> Option<&'a str> {
+        self.string(self.u8(off)?)
+    }
+}
+
+/// A typed view of [`SmbiosStructure`].
+pub trait SmbiosType<'a> {
+    const TYPE: u8;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self;
+}
+
+/// BIOS Information (Type 0).
+#[derive(Clone, Copy)]
+pub struct BiosInfo<'a>(SmbiosStructure<'a>);
+
+impl<'a> BiosInfo<'a> {
+    pub fn structure(&self) -> &SmbiosStructure<'a> {
+        &self.0
+    }
+
+    pub fn vendor(&self) -> Option<&'a str> {
+        self.0.str(4)
+    }
+
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.str(5)
+    }
+
+    pub fn starting_segment(&self) -> Option<u16> {
+        self.0.u16(6)
+    }
+
+    pub fn release_date(&self) -> Option<&'a str> {
+        self.0.str(8)
+    }
+
+    /// Returns the size of the BIOS ROM in bytes.
+    pub fn rom_size(&self) -> Option<u64> {
+        self.0.u8(9).map(|v| (u64::from(v) + 1) * 64 * 1024)
+    }
+
+    pub fn characteristics(&self) -> Option<u64> {
+        self.0.u64(0xA)
+    }
+
+    /// Returns the major and minor release of the BIOS. Returns [`None`] if not available.
+    pub fn release(&self) -> Option<(u8, u8)> {
+        match (self.0.u8(0x14)?, self.0.u8(0x15)?) {
+            (0xFF, 0xFF) => None,
+            v => Some(v),
+        }
+    }
+}
+
+impl<'a> SmbiosType<'a> for BiosInfo<'a> {
+    const TYPE: u8 = 0;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self {
+        Self(s)
+    }
+}
+
+/// System Information (Type 1).
+#[derive(Clone, Copy)]
+pub struct SystemInfo<'a>(SmbiosStructure<'a>);
+
+impl<'a> SystemInfo<'a> {
+    pub fn structure(&self) -> &SmbiosStructure<'a> {
+        &self.0
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.str(4)
+    }
+
+    pub fn product_name(&self) -> Option<&'a str> {
+        self.0.str(5)
+    }
+
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.str(6)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.str(7)
+    }
+
+    /// Returns [`None`] if the UUID is not present.
+    pub fn uuid(&self) -> Option<Guid> {
+        let b: [u8; 16] = self.0.data.get(8..0x18)?.try_into().unwrap();
+
+        if b == [0xFF; 16] {
+            None
+        } else if self.0.version < (2, 6) {
+            // Prior to SMBIOS 2.6 the UUID was encoded in network byte order.
+            Some(Guid::new(
+                u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+                u16::from_be_bytes([b[4], b[5]]),
+                u16::from_be_bytes([b[6], b[7]]),
+                b[8..].try_into().unwrap(),
+            ))
+        } else {
+            Some(Guid::from_bytes(b))
+        }
+    }
+
+    pub fn wake_up_type(&self) -> Option<u8> {
+        self.0.u8(0x18)
+    }
+
+    pub fn sku_number(&self) -> Option<&'a str> {
+        self.0.str(0x19)
+    }
+
+    pub fn family(&self) -> Option<&'a str> {
+        self.0.str(0x1A)
+    }
+}
+
+impl<'a> SmbiosType<'a> for SystemInfo<'a> {
+    const TYPE: u8 = 1;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self {
+        Self(s)
+    }
+}
+
+/// Baseboard Information (Type 2).
+#[derive(Clone, Copy)]
+pub struct BaseboardInfo<'a>(SmbiosStructure<'a>);
+
+impl<'a> BaseboardInfo<'a> {
+    pub fn structure(&self) -> &SmbiosStructure<'a> {
+        &self.0
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.str(4)
+    }
+
+    pub fn product(&self) -> Option<&'a str> {
+        self.0.str(5)
+    }
+
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.str(6)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.str(7)
+    }
+
+    pub fn asset_tag(&self) -> Option<&'a str> {
+        self.0.str(8)
+    }
+
+    pub fn feature_flags(&self) -> Option<u8> {
+        self.0.u8(9)
+    }
+
+    pub fn location_in_chassis(&self) -> Option<&'a str> {
+        self.0.str(0xA)
+    }
+
+    pub fn chassis_handle(&self) -> Option<u16> {
+        self.0.u16(0xB)
+    }
+
+    pub fn board_type(&self) -> Option<u8> {
+        self.0.u8(0xD)
+    }
+}
+
+impl<'a> SmbiosType<'a> for BaseboardInfo<'a> {
+    const TYPE: u8 = 2;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self {
+        Self(s)
+    }
+}
+
+/// Processor Information (Type 4).
+#[derive(Clone, Copy)]
+pub struct ProcessorInfo<'a>(SmbiosStructure<'a>);
+
+impl<'a> ProcessorInfo<'a> {
+    pub fn structure(&self) -> &SmbiosStructure<'a> {
+        &self.0
+    }
+
+    pub fn socket_designation(&self) -> Option<&'a str> {
+        self.0.str(4)
+    }
+
+    pub fn processor_type(&self) -> Option<u8> {
+        self.0.u8(5)
+    }
+
+    /// Returns the value of Processor Family 2 if Processor Family is `0xFE`.
+    pub fn processor_family(&self) -> Option<u16> {
+        match self.0.u8(6)? {
+            0xFE => self.0.u16(0x28),
+            v => Some(v.into()),
+        }
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.str(7)
+    }
+
+    pub fn processor_id(&self) -> Option<u64> {
+        self.0.u64(8)
+    }
+
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.str(0x10)
+    }
+
+    /// Returns the external clock frequency in MHz. Returns [`None`] if unknown.
+    pub fn external_clock(&self) -> Option<u16> {
+        self.0.u16(0x12).filter(|&v| v != 0)
+    }
+
+    /// Returns the maximum speed in MHz. Returns [`None`] if unknown.
+    pub fn max_speed(&self) -> Option<u16> {
+        self.0.u16(0x14).filter(|&v| v != 0)
+    }
+
+    /// Returns the current speed in MHz. Returns [`None`] if unknown.
+    pub fn current_speed(&self) -> Option<u16> {
+        self.0.u16(0x16).filter(|&v| v != 0)
+    }
+
+    pub fn status(&self) -> Option<u8> {
+        self.0.u8(0x18)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.str(0x20)
+    }
+
+    pub fn asset_tag(&self) -> Option<&'a str> {
+        self.0.str(0x21)
+    }
+
+    pub fn part_number(&self) -> Option<&'a str> {
+        self.0.str(0x22)
+    }
+
+    /// Returns [`None`] if unknown.
+    pub fn core_count(&self) -> Option<u16> {
+        match self.0.u8(0x23)? {
+            0 => None,
+            0xFF => self.0.u16(0x2A).filter(|&v| v != 0),
+            v => Some(v.into()),
+        }
+    }
+
+    /// Returns [`None`] if unknown.
+    pub fn core_enabled(&self) -> Option<u16> {
+        match self.0.u8(0x24)? {
+            0 => None,
+            0xFF => self.0.u16(0x2C).filter(|&v| v != 0),
+            v => Some(v.into()),
+        }
+    }
+
+    /// Returns [`None`] if unknown.
+    pub fn thread_count(&self) -> Option<u16> {
+        match self.0.u8(0x25)? {
+            0 => None,
+            0xFF => self.0.u16(0x2E).filter(|&v| v != 0),
+            v => Some(v.into()),
+        }
+    }
+
+    pub fn characteristics(&self) -> Option<u16> {
+        self.0.u16(0x26)
+    }
+}
+
+impl<'a> SmbiosType<'a> for ProcessorInfo<'a> {
+    const TYPE: u8 = 4;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self {
+        Self(s)
+    }
+}
+
+/// Memory Device (Type 17).
+#[derive(Clone, Copy)]
+pub struct MemoryDevice<'a>(SmbiosStructure<'a>);
+
+impl<'a> MemoryDevice<'a> {
+    pub fn structure(&self) -> &SmbiosStructure<'a> {
+        &self.0
+    }
+
+    pub fn physical_memory_array_handle(&self) -> Option<u16> {
+        self.0.u16(4)
+    }
+
+    /// Returns the total width in bits, including ECC bits. Returns [`None`] if unknown.
+    pub fn total_width(&self) -> Option<u16> {
+        self.0.u16(8).filter(|&v| v != 0xFFFF)
+    }
+
+    /// Returns the data width in bits. Returns [`None`] if unknown.
+    pub fn data_width(&self) -> Option<u16> {
+        self.0.u16(0xA).filter(|&v| v != 0xFFFF)
+    }
+
+    /// Returns the size of the memory device in bytes. Returns zero if no memory device is
+    /// installed in the socket or [`None`] if unknown.
+    pub fn size(&self) -> Option<u64> {
+        match self.0.u16(0xC)? {
+            0xFFFF => None,
+            0x7FFF => self
+                .0
+                .u32(0x1C)
+                .map(|v| u64::from(v & 0x7FFFFFFF) * 1024 * 1024),
+            v if v & 0x8000 != 0 => Some(u64::from(v & 0x7FFF) * 1024),
+            v => Some(u64::from(v) * 1024 * 1024),
+        }
+    }
+
+    pub fn form_factor(&self) -> Option<u8> {
+        self.0.u8(0xE)
+    }
+
+    pub fn device_locator(&self) -> Option<&'a str> {
+        self.0.str(0x10)
+    }
+
+    pub fn bank_locator(&self) -> Option<&'a str> {
+        self.0.str(0x11)
+    }
+
+    pub fn memory_type(&self) -> Option<u8> {
+        self.0.u8(0x12)
+    }
+
+    /// Returns the maximum speed in MT/s. Returns [`None`] if unknown.
+    pub fn speed(&self) -> Option<u16> {
+        self.0.u16(0x15).filter(|&v| v != 0)
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.str(0x17)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.str(0x18)
+    }
+
+    pub fn asset_tag(&self) -> Option<&'a str> {
+        self.0.str(0x19)
+    }
+
+    pub fn part_number(&self) -> Option<&'a str> {
+        self.0.str(0x1A)
+    }
+}
+
+impl<'a> SmbiosType<'a> for MemoryDevice<'a> {
+    const TYPE: u8 = 17;
+
+    fn from_structure(s: SmbiosStructure<'a>) -> Self {
+        Self(s)
+    }
+}
+
+/// Represents an error when [`Smbios::find()`] or [`SmbiosEntryPoint::parse()`] is failed.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SmbiosError {
+    #[error("no SMBIOS table")]
+    NotFound,
+
+    #[error("invalid anchor string")]
+    InvalidAnchor,
+
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("invalid checksum")]
+    InvalidChecksum,
+}
+
+fn checksum(data: &[u8]) -> bool {
+    data.iter().fold(0u8, |s, &v| s.wrapping_add(v)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn entry_point_v2() {
+        let mut ep = [0u8; 0x1F];
+
+        ep[..4].copy_from_slice(b"_SM_");
+        ep[5] = 0x1F;
+        ep[6] = 2;
+        ep[7] = 8;
+        ep[0x10..0x15].copy_from_slice(b"_DMI_");
+        ep[0x16..0x18].copy_from_slice(&0x123u16.to_le_bytes());
+        ep[0x18..0x1C].copy_from_slice(&0xF0000u32.to_le_bytes());
+        ep[0x1C..0x1E].copy_from_slice(&5u16.to_le_bytes());
+        ep[0x15] = fix(&ep[0x10..0x1F]);
+        ep[4] = fix(&ep);
+
+        let ep = SmbiosEntryPoint::parse(&ep).unwrap();
+
+        assert_eq!(ep.version(), (2, 8));
+        assert_eq!(ep.table_address(), 0xF0000);
+        assert_eq!(ep.table_length(), 0x123);
+    }
+
+    #[test]
+    fn entry_point_v3() {
+        let mut ep = [0u8; 0x18];
+
+        ep[..5].copy_from_slice(b"_SM3_");
+        ep[6] = 0x18;
+        ep[7] = 3;
+        ep[8] = 2;
+        ep[0xC..0x10].copy_from_slice(&0x400u32.to_le_bytes());
+        ep[0x10..0x18].copy_from_slice(&0x1_0000_0000u64.to_le_bytes());
+        ep[5] = fix(&ep);
+
+        let ep = SmbiosEntryPoint::parse(&ep).unwrap();
+
+        assert_eq!(ep.version(), (3, 2));
+        assert_eq!(ep.table_address(), 0x1_0000_0000);
+        assert_eq!(ep.table_length(), 0x400);
+
+        // Corrupt the checksum.
+        let mut ep = [0u8; 0x18];
+
+        ep[..5].copy_from_slice(b"_SM3_");
+        ep[6] = 0x18;
+        ep[7] = 1;
+
+        assert_eq!(
+            SmbiosEntryPoint::parse(&ep),
+            Err(SmbiosError::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn structures() {
+        let ep = SmbiosEntryPoint {
+            major: 3,
+            minor: 0,
+            count: None,
+            table_address: 0,
+            table_length: 0,
+        };
+
+        let mut table = Vec::new();
+
+        // Type 1.
+        let mut s1 = [0u8; 0x1B];
+
+        s1[0] = 1;
+        s1[1] = 0x1B;
+        s1[2..4].copy_from_slice(&0x100u16.to_le_bytes());
+        s1[4] = 1;
+        s1[5] = 2;
+        s1[7] = 3;
+        s1[8..0x18].copy_from_slice(&[
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ]);
+
+        table.extend_from_slice(&s1);
+        table.extend_from_slice(b"ZFI\0Machine\0SN123\0\0");
+
+        // Type 17 without strings.
+        let mut s17 = [0u8; 0x22];
+
+        s17[0] = 17;
+        s17[1] = 0x22;
+        s17[0xC..0xE].copy_from_slice(&0x7FFFu16.to_le_bytes());
+        s17[0x1C..0x20].copy_from_slice(&0x8000u32.to_le_bytes());
+
+        table.extend_from_slice(&s17);
+        table.extend_from_slice(b"\0\0");
+
+        // End-of-table and a garbage after it.
+        table.extend_from_slice(&[127, 4, 0, 0, 0, 0]);
+        table.extend_from_slice(&[1, 0x1B]);
+
+        // Check.
+        let smbios = Smbios::new(&ep, &table);
+        let sys: SystemInfo = smbios.structure().unwrap();
+
+        assert_eq!(smbios.structures().count(), 2);
+        assert_eq!(sys.structure().handle(), 0x100);
+        assert_eq!(sys.manufacturer(), Some("ZFI"));
+        assert_eq!(sys.product_name(), Some("Machine"));
+        assert_eq!(sys.version(), None);
+        assert_eq!(sys.serial_number(), Some("SN123"));
+        assert_eq!(
+            sys.uuid().unwrap().to_string(),
+            "00112233-4455-6677-8899-aabbccddeeff"
+        );
+        assert_eq!(sys.sku_number(), None);
+
+        let mem: MemoryDevice = smbios.structure().unwrap();
+
+        assert_eq!(mem.size(), Some(0x8000 * 1024 * 1024));
+        assert_eq!(mem.manufacturer(), None);
+        assert!(smbios.structure::<BiosInfo>().is_none());
+    }
+
+    #[test]
+    fn uuid_v2() {
+        let ep = SmbiosEntryPoint {
+            major: 2,
+            minor: 4,
+            count: Some(1),
+            table_address: 0,
+            table_length: 0,
+        };
+
+        let mut table = Vec::new();
+        let mut s1 = [0u8; 0x19];
+
+        s1[0] = 1;
+        s1[1] = 0x19;
+        s1[8..0x18].copy_from_slice(&[
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ]);
+
+        table.extend_from_slice(&s1);
+        table.extend_from_slice(b"\0\0");
+
+        // Check.
+        let smbios = Smbios::new(&ep, &table);
+        let sys: SystemInfo = smbios.structure().unwrap();
+
+        assert_eq!(
+            sys.uuid().unwrap().to_string(),
+            "00112233-4455-6677-8899-aabbccddeeff"
+        );
+    }
+
+    #[test]
+    fn processor_family() {
+        let ep = SmbiosEntryPoint {
+            major: 3,
+            minor: 0,
+            count: None,
+            table_address: 0,
+            table_length: 0,
+        };
+
+        let mut table = Vec::new();
+
+        // Type 4 with Processor Family 2.
+        let mut s4 = [0u8; 0x30];
+
+        s4[0] = 4;
+        s4[1] = 0x30;
+        s4[6] = 0xFE;
+        s4[0x28..0x2A].copy_from_slice(&0x118u16.to_le_bytes());
+
+        table.extend_from_slice(&s4);
+        table.extend_from_slice(b"\0\0");
+
+        // Type 4 without Processor Family 2.
+        s4[6] = 0xB3;
+        s4[0x28..0x2A].fill(0);
+
+        table.extend_from_slice(&s4);
+        table.extend_from_slice(b"\0\0");
+
+        // Check.
+        let smbios = Smbios::new(&ep, &table);
+        let families: Vec<u16> = smbios
+            .structures_of::<ProcessorInfo>()
+            .map(|p| p.processor_family().unwrap())
+            .collect();
+
+        assert_eq!(families, [0x118, 0xB3]);
+    }
+
+    fn fix(data: &[u8]) -> u8 {
+        0u8.wrapping_sub(data.iter().fold(0u8, |s, &v| s.wrapping_add(v)))
+    }
+}
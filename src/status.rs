@@ -9,9 +9,19 @@ pub struct Status(usize);
 
 impl Status {
     pub const SUCCESS: Self = Self(0);
+    pub const INVALID_PARAMETER: Self = Self::error(2);
     pub const UNSUPPORTED: Self = Self::error(3);
     pub const BUFFER_TOO_SMALL: Self = Self::error(5);
+    pub const NOT_READY: Self = Self::error(6);
+    pub const DEVICE_ERROR: Self = Self::error(7);
+    pub const WRITE_PROTECTED: Self = Self::error(8);
+    pub const OUT_OF_RESOURCES: Self = Self::error(9);
+    pub const NOT_FOUND: Self = Self::error(14);
+    pub const ACCESS_DENIED: Self = Self::error(15);
+    pub const ALREADY_STARTED: Self = Self::error(20);
     pub const ABORTED: Self = Self::error(21);
+    pub const SECURITY_VIOLATION: Self = Self::error(26);
+    pub const COMPROMISED_DATA: Self = Self::error(33);
 
     #[cfg(target_pointer_width = "32")]
     const fn error(v: usize) -> Self {
@@ -42,9 +52,21 @@ impl Display for Status {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match *self {
             Self::SUCCESS => f.write_str("the operation completed successfully"),
+            Self::INVALID_PARAMETER => f.write_str("a parameter was incorrect"),
             Self::UNSUPPORTED => f.write_str("the operation is not supported"),
             Self::BUFFER_TOO_SMALL => f.write_str("the buffer is not large enough"),
+            Self::NOT_READY => f.write_str("there is no data pending upon return"),
+            Self::DEVICE_ERROR => f.write_str("the physical device reported an error"),
+            Self::WRITE_PROTECTED => f.write_str("the device cannot be written to"),
+            Self::OUT_OF_RESOURCES => f.write_str("a resource has run out"),
+            Self::NOT_FOUND => f.write_str("the item was not found"),
+            Self::ACCESS_DENIED => f.write_str("access was denied"),
+            Self::ALREADY_STARTED => f.write_str("the protocol has already been started"),
             Self::ABORTED => f.write_str("the operation was aborted"),
+            Self::SECURITY_VIOLATION => {
+                f.write_str("the function was not performed due to a security violation")
+            }
+            Self::COMPROMISED_DATA => f.write_str("the data is compromised"),
             v => write!(f, "{:#x}", v.0),
         }
     }
@@ -120,6 +120,16 @@ impl core::fmt::Display for Display<'_> {
 pub struct EfiString(Vec<u16>);
 
 impl EfiString {
+    /// # Safety
+    /// `data` must be:
+    ///
+    /// - NUL-terminated.
+    /// - Not have any NULs in the middle.
+    /// - Valid UCS-2 (not UTF-16).
+    pub unsafe fn new_unchecked(data: Vec<u16>) -> Self {
+        Self(data)
+    }
+
     pub fn push(&mut self, c: EfiChar) {
         self.0.pop();
         self.0.push(c.0);
@@ -1,4 +1,7 @@
-use crate::{BootServices, RuntimeServices, SimpleTextInput, SimpleTextOutput, TableHeader};
+use crate::{
+    BootServices, EfiStr, Guid, RuntimeServices, SimpleTextInput, SimpleTextOutput, TableHeader,
+};
+use core::slice::from_raw_parts;
 
 /// Represents an `EFI_SYSTEM_TABLE`.
 #[repr(C)]
@@ -14,6 +17,8 @@ pub struct SystemTable {
     std_err: *const SimpleTextOutput,
     runtime_services: *const RuntimeServices,
     boot_services: *const BootServices,
+    number_of_table_entries: usize,
+    configuration_table: *const ConfigurationTable,
 }
 
 impl SystemTable {
@@ -21,6 +26,16 @@ impl SystemTable {
         &self.hdr
     }
 
+    /// Returns the vendor of the firmware.
+    pub fn firmware_vendor(&self) -> &EfiStr {
+        unsafe { EfiStr::from_ptr(self.firmware_vendor) }
+    }
+
+    /// Returns a vendor specific revision of the firmware.
+    pub fn firmware_revision(&self) -> u32 {
+        self.firmware_revision
+    }
+
     pub fn stdin(&self) -> &SimpleTextInput {
         // SAFETY: This is safe because we mark ExitBootServices() as unsafe.
         unsafe { &*self.con_in }
@@ -40,4 +55,32 @@ impl SystemTable {
         // SAFETY: This is safe because we mark ExitBootServices() as unsafe.
         unsafe { &*self.boot_services }
     }
+
+    pub fn runtime_services(&self) -> &RuntimeServices {
+        unsafe { &*self.runtime_services }
+    }
+
+    /// Returns an iterator over the GUID and the pointer of each configuration table.
+    pub fn config_tables(&self) -> impl Iterator<Item = (Guid, *const ())> + '_ {
+        let tables = match self.number_of_table_entries {
+            0 => &[],
+            n => unsafe { from_raw_parts(self.configuration_table, n) },
+        };
+
+        tables.iter().map(|t| (t.vendor_guid, t.vendor_table))
+    }
+
+    /// Returns a pointer to the configuration table identified by `guid`.
+    pub fn find_config_table(&self, guid: &Guid) -> Option<*const ()> {
+        self.config_tables()
+            .find(|(g, _)| g == guid)
+            .map(|(_, t)| t)
+    }
+}
+
+/// Represents an `EFI_CONFIGURATION_TABLE`.
+#[repr(C)]
+struct ConfigurationTable {
+    vendor_guid: Guid,
+    vendor_table: *const (),
 }
@@ -1,5 +1,136 @@
+use crate::{locate_protocol, system_table, Status, Timestamp, TimestampProperties};
+use bitflags::bitflags;
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+use thiserror::Error;
+
+/// Stalls the processor for at least `dur`.
+///
+/// This is a busy-wait with `EFI_BOOT_SERVICES.Stall()`. A long duration will be divided into
+/// multiple stalls of one second.
+pub fn sleep(dur: Duration) {
+    let bs = system_table().boot_services();
+    let mut remaining = dur.as_micros();
+
+    // Round up to microseconds.
+    if dur.subsec_nanos() % 1000 != 0 {
+        remaining += 1;
+    }
+
+    while remaining != 0 {
+        let us = remaining.min(1000000);
+
+        // Stall() is always success.
+        let _ = bs.stall(us as usize);
+
+        remaining -= us;
+    }
+}
+
+/// A value from `EFI_BOOT_SERVICES.GetNextMonotonicCount()`.
+///
+/// The only guarantee of this type is an instant that was obtained later is always greater than the
+/// earlier one. It is not a measurement of time since the firmware only increase the count by one
+/// each time it is obtained. Use [`PreciseInstant`] to measure an elapsed time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Obtains the next monotonic count.
+    pub fn now() -> Result<Self, Status> {
+        system_table()
+            .boot_services()
+            .get_next_monotonic_count()
+            .map(Self)
+    }
+
+    /// Returns the raw monotonic count.
+    pub fn count(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns how many counts was obtained since `earlier` or [`None`] if `earlier` is later than
+    /// this instant.
+    pub fn counts_since(&self, earlier: Self) -> Option<u64> {
+        self.0.checked_sub(earlier.0)
+    }
+}
+
+/// A high-resolution instant from `EFI_TIMESTAMP_PROTOCOL`.
+///
+/// Unlike [`Instant`], this type can be used to measure an elapsed time. The counter is assumed to
+/// roll over at most once between two instants.
+#[derive(Clone, Copy)]
+pub struct PreciseInstant {
+    timer: &'static Timestamp,
+    props: TimestampProperties,
+    value: u64,
+}
+
+impl PreciseInstant {
+    /// Returns [`None`] if the firmware does not provide `EFI_TIMESTAMP_PROTOCOL`.
+    pub fn now() -> Option<Self> {
+        let timer = locate_protocol::<Timestamp>()?;
+        let props = timer.properties().ok()?;
+
+        if props.frequency() == 0 {
+            return None;
+        }
+
+        Some(Self {
+            timer,
+            props,
+            value: timer.get(),
+        })
+    }
+
+    /// Returns the raw value of the timestamp counter.
+    pub fn ticks(&self) -> u64 {
+        self.value
+    }
+
+    /// Returns the frequency of the timestamp counter in Hz.
+    pub fn frequency(&self) -> u64 {
+        self.props.frequency()
+    }
+
+    /// Returns the amount of time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        self.duration_to(self.timer.get())
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to this instant.
+    pub fn duration_since(&self, earlier: &Self) -> Duration {
+        earlier.duration_to(self.value)
+    }
+
+    fn duration_to(&self, later: u64) -> Duration {
+        // Get number of ticks, including when the counter was rolled over.
+        let ticks = if later >= self.value {
+            later - self.value
+        } else {
+            self.props
+                .end_value()
+                .wrapping_sub(self.value)
+                .wrapping_add(later)
+                .wrapping_add(1)
+        };
+
+        // Convert to Duration.
+        let freq = self.props.frequency();
+        let secs = ticks / freq;
+        let nanos = u128::from(ticks % freq) * 1000000000 / u128::from(freq);
+
+        Duration::new(secs, nanos as u32)
+    }
+}
+
 /// Represents an `EFI_TIME`.
+///
+/// The time zone is the offset in minutes from UTC (`Localtime = UTC + TimeZone`). The daylight
+/// saving time is not included in the time zone.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Time {
     year: u16,
     month: u8,
@@ -13,3 +144,481 @@ pub struct Time {
     daylight: u8,
     pad2: u8,
 }
+
+impl Time {
+    /// A time with all fields set to zero, which is not a valid time.
+    ///
+    /// This value has a special meaning for `EFI_FILE_PROTOCOL.SetInfo()`, which is not to change
+    /// the time.
+    pub const ZERO: Self = Self {
+        year: 0,
+        month: 0,
+        day: 0,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        pad1: 0,
+        nanosecond: 0,
+        time_zone: 0,
+        daylight: 0,
+        pad2: 0,
+    };
+
+    const UNSPECIFIED_TIMEZONE: i16 = 0x07FF;
+
+    /// Creates a local time without the time zone.
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<Self, TimeError> {
+        let time = Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            pad1: 0,
+            nanosecond: 0,
+            time_zone: Self::UNSPECIFIED_TIMEZONE,
+            daylight: 0,
+            pad2: 0,
+        };
+
+        time.validate()?;
+
+        Ok(time)
+    }
+
+    /// Creates a UTC time from the number of seconds and nanoseconds since the Unix epoch.
+    pub fn from_unix(secs: i64, nanosecond: u32) -> Result<Self, TimeError> {
+        if nanosecond >= 1000000000 {
+            return Err(TimeError::InvalidNanosecond(nanosecond));
+        }
+
+        // Split the date and the time.
+        let days = secs.div_euclid(86400);
+        let secs = secs.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+
+        if !(1900..=9999).contains(&year) {
+            return Err(TimeError::OutOfRange);
+        }
+
+        Ok(Self {
+            year: year as u16,
+            month,
+            day,
+            hour: (secs / 3600) as u8,
+            minute: (secs / 60 % 60) as u8,
+            second: (secs % 60) as u8,
+            pad1: 0,
+            nanosecond,
+            time_zone: 0,
+            daylight: 0,
+            pad2: 0,
+        })
+    }
+
+    /// Returns the current time from the real-time clock.
+    pub fn now() -> Result<Self, Status> {
+        system_table().runtime_services().get_time().map(|(t, _)| t)
+    }
+
+    /// Returns a copy of this time with the nanosecond set to `v`.
+    pub fn with_nanosecond(mut self, v: u32) -> Result<Self, TimeError> {
+        if v >= 1000000000 {
+            return Err(TimeError::InvalidNanosecond(v));
+        }
+
+        self.nanosecond = v;
+
+        Ok(self)
+    }
+
+    /// `v` is the offset in minutes from UTC or [`None`] for a local time.
+    pub fn with_time_zone(mut self, v: Option<i16>) -> Result<Self, TimeError> {
+        self.time_zone = match v {
+            Some(v) if (-1440..=1440).contains(&v) => v,
+            Some(v) => return Err(TimeError::InvalidTimeZone(v)),
+            None => Self::UNSPECIFIED_TIMEZONE,
+        };
+
+        Ok(self)
+    }
+
+    /// Returns a copy of this time with the daylight saving time information set to `v`.
+    pub fn with_daylight(mut self, v: Daylight) -> Self {
+        self.daylight = v.bits();
+        self
+    }
+
+    /// 1900 – 9999.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// 1 – 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// 1 – 31.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// 0 – 23.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// 0 – 59.
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// 0 – 59.
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// 0 – 999,999,999.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Returns the offset in minutes from UTC or [`None`] if this is a local time.
+    pub fn time_zone(&self) -> Option<i16> {
+        match self.time_zone {
+            Self::UNSPECIFIED_TIMEZONE => None,
+            v => Some(v),
+        }
+    }
+
+    /// Returns the daylight saving time information.
+    pub fn daylight(&self) -> Daylight {
+        Daylight::from_bits_retain(self.daylight)
+    }
+
+    /// Checks if all fields are within the range defined by the UEFI specification.
+    pub fn validate(&self) -> Result<(), TimeError> {
+        if !(1900..=9999).contains(&self.year) {
+            Err(TimeError::InvalidYear(self.year))
+        } else if !(1..=12).contains(&self.month) {
+            Err(TimeError::InvalidMonth(self.month))
+        } else if self.day == 0 || self.day > days_in_month(self.year, self.month) {
+            Err(TimeError::InvalidDay(self.day))
+        } else if self.hour > 23 {
+            Err(TimeError::InvalidHour(self.hour))
+        } else if self.minute > 59 {
+            Err(TimeError::InvalidMinute(self.minute))
+        } else if self.second > 59 {
+            Err(TimeError::InvalidSecond(self.second))
+        } else if self.nanosecond >= 1000000000 {
+            Err(TimeError::InvalidNanosecond(self.nanosecond))
+        } else if self.time_zone != Self::UNSPECIFIED_TIMEZONE
+            && !(-1440..=1440).contains(&self.time_zone)
+        {
+            Err(TimeError::InvalidTimeZone(self.time_zone))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the number of seconds since the Unix epoch. Use [`Self::nanosecond()`] for the
+    /// fractional part.
+    ///
+    /// The local time will be treated as UTC without any adjustment. [`Daylight::IN_DAYLIGHT`] will
+    /// subtract one hour from the result only if the time zone is specified.
+    pub fn to_unix(&self) -> Result<i64, TimeError> {
+        self.validate()?;
+
+        // Get local seconds.
+        let days = days_from_civil(self.year.into(), self.month, self.day);
+        let mut secs = days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+
+        // Convert to UTC.
+        if let Some(v) = self.time_zone() {
+            secs -= i64::from(v) * 60;
+
+            if self.daylight().contains(Daylight::IN_DAYLIGHT) {
+                secs -= 3600;
+            }
+        }
+
+        Ok(secs)
+    }
+}
+
+/// Formats the time in ISO 8601 (e.g. `2024-02-29T13:45:00.5+07:00`). The offset will be omitted
+/// for a local time.
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+
+        // Write fractional part.
+        if self.nanosecond != 0 {
+            let mut ns = self.nanosecond;
+            let mut digits = 9;
+
+            while ns % 10 == 0 {
+                ns /= 10;
+                digits -= 1;
+            }
+
+            write!(f, ".{ns:0digits$}")?;
+        }
+
+        // Write offset.
+        let offset = match self.time_zone() {
+            Some(v) => {
+                if self.daylight().contains(Daylight::IN_DAYLIGHT) {
+                    v + 60
+                } else {
+                    v
+                }
+            }
+            None => return Ok(()),
+        };
+
+        if offset == 0 {
+            f.write_str("Z")
+        } else {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.unsigned_abs();
+
+            write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+        }
+    }
+}
+
+bitflags! {
+    /// Daylight saving time information of [`Time`].
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Daylight: u8 {
+        /// The time should be adjusted for the daylight saving time.
+        const ADJUST_DAYLIGHT = 0x01;
+
+        /// The time is currently in the daylight saving time.
+        const IN_DAYLIGHT = 0x02;
+    }
+}
+
+/// Represents an `EFI_TIME_CAPABILITIES`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TimeCapabilities {
+    resolution: u32,
+    accuracy: u32,
+    sets_to_zero: u8, // BOOLEAN.
+}
+
+impl TimeCapabilities {
+    /// Returns the resolution of the real-time clock in counts per second.
+    pub fn resolution(&self) -> u32 {
+        self.resolution
+    }
+
+    /// Returns the error rate of the real-time clock in 1E-6 parts per million.
+    pub fn accuracy(&self) -> u32 {
+        self.accuracy
+    }
+
+    /// Returns `true` if setting the time will clear the value below the resolution.
+    pub fn sets_to_zero(&self) -> bool {
+        self.sets_to_zero != 0
+    }
+}
+
+/// Result of [`crate::RuntimeServices::get_wakeup_time()`].
+#[derive(Debug, Clone, Copy)]
+pub struct WakeupTime {
+    pub(crate) enabled: bool,
+    pub(crate) pending: bool,
+    pub(crate) time: Time,
+}
+
+impl WakeupTime {
+    /// Returns `true` if the alarm is enabled.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns `true` if the alarm has been signaled.
+    pub fn pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Returns the time of the alarm.
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+}
+
+/// Represents an error when [`Time`] is not valid.
+#[derive(Debug, Error)]
+pub enum TimeError {
+    #[error("invalid year {0}")]
+    InvalidYear(u16),
+
+    #[error("invalid month {0}")]
+    InvalidMonth(u8),
+
+    #[error("invalid day {0}")]
+    InvalidDay(u8),
+
+    #[error("invalid hour {0}")]
+    InvalidHour(u8),
+
+    #[error("invalid minute {0}")]
+    InvalidMinute(u8),
+
+    #[error("invalid second {0}")]
+    InvalidSecond(u8),
+
+    #[error("invalid nanosecond {0}")]
+    InvalidNanosecond(u32),
+
+    #[error("invalid time zone {0}")]
+    InvalidTimeZone(i16),
+
+    #[error("the time is out of range")]
+    OutOfRange,
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since 1970-01-01.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    // See http://howardhinnant.github.io/date_algorithms.html for the algorithm.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Returns the year, month and day from the number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn unix() {
+        let t = Time::from_unix(0, 0).unwrap();
+
+        assert_eq!((t.year(), t.month(), t.day(), t.hour()), (1970, 1, 1, 0));
+        assert_eq!(t.time_zone(), Some(0));
+        assert_eq!(t.to_unix().unwrap(), 0);
+
+        // Leap day.
+        let t = Time::from_unix(1709214300, 5).unwrap();
+
+        assert_eq!((t.year(), t.month(), t.day()), (2024, 2, 29));
+        assert_eq!((t.hour(), t.minute(), t.second()), (13, 45, 0));
+        assert_eq!(t.nanosecond(), 5);
+        assert_eq!(t.to_unix().unwrap(), 1709214300);
+
+        // Before the epoch.
+        let t = Time::from_unix(-1, 0).unwrap();
+
+        assert_eq!((t.year(), t.month(), t.day()), (1969, 12, 31));
+        assert_eq!((t.hour(), t.minute(), t.second()), (23, 59, 59));
+        assert!(Time::from_unix(i64::MAX, 0).is_err());
+
+        // Time zone and daylight.
+        let t = Time::new(2024, 2, 29, 20, 45, 0)
+            .unwrap()
+            .with_time_zone(Some(420))
+            .unwrap();
+
+        assert_eq!(t.to_unix().unwrap(), 1709214300);
+        assert_eq!(
+            t.with_daylight(Daylight::IN_DAYLIGHT).to_unix().unwrap(),
+            1709214300 - 3600
+        );
+
+        // Daylight without time zone.
+        let t = Time::new(2024, 2, 29, 13, 45, 0)
+            .unwrap()
+            .with_daylight(Daylight::IN_DAYLIGHT);
+
+        assert_eq!(t.to_unix().unwrap(), 1709214300);
+    }
+
+    #[test]
+    fn validate() {
+        assert!(Time::new(2023, 2, 29, 0, 0, 0).is_err());
+        assert!(Time::new(2000, 2, 29, 0, 0, 0).is_ok());
+        assert!(Time::new(1900, 2, 29, 0, 0, 0).is_err());
+        assert!(Time::new(2024, 13, 1, 0, 0, 0).is_err());
+        assert!(Time::new(2024, 1, 1, 24, 0, 0).is_err());
+        assert!(Time::new(1899, 1, 1, 0, 0, 0).is_err());
+        assert!(Time::ZERO.validate().is_err());
+        assert!(Time::ZERO.with_time_zone(Some(1441)).is_err());
+    }
+
+    #[test]
+    fn display() {
+        let t = Time::new(2024, 2, 29, 13, 45, 0).unwrap();
+
+        assert_eq!(t.to_string(), "2024-02-29T13:45:00");
+        assert_eq!(
+            t.with_nanosecond(500000000).unwrap().to_string(),
+            "2024-02-29T13:45:00.5"
+        );
+        assert_eq!(
+            t.with_time_zone(Some(0)).unwrap().to_string(),
+            "2024-02-29T13:45:00Z"
+        );
+        assert_eq!(
+            t.with_time_zone(Some(-330)).unwrap().to_string(),
+            "2024-02-29T13:45:00-05:30"
+        );
+        assert_eq!(
+            t.with_time_zone(Some(420))
+                .unwrap()
+                .with_daylight(Daylight::IN_DAYLIGHT)
+                .to_string(),
+            "2024-02-29T13:45:00+08:00"
+        );
+    }
+}
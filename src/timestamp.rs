@@ -0,0 +1,60 @@
+use crate::{Guid, Protocol, Status};
+
+/// Represents an `EFI_TIMESTAMP_PROTOCOL`.
+#[repr(C)]
+pub struct Timestamp {
+    get_timestamp: extern "efiapi" fn() -> u64,
+    get_properties: unsafe extern "efiapi" fn(*mut TimestampProperties) -> Status,
+}
+
+impl Timestamp {
+    /// Returns the current value of the timestamp counter.
+    pub fn get(&self) -> u64 {
+        (self.get_timestamp)()
+    }
+
+    /// Returns the properties of the timestamp counter.
+    pub fn properties(&self) -> Result<TimestampProperties, Status> {
+        let mut props = TimestampProperties {
+            frequency: 0,
+            end_value: 0,
+        };
+
+        let status = unsafe { (self.get_properties)(&mut props) };
+
+        if status != Status::SUCCESS {
+            Err(status)
+        } else {
+            Ok(props)
+        }
+    }
+}
+
+unsafe impl Protocol for Timestamp {
+    const ID: Guid = Guid::new(
+        0xafbfde41,
+        0x2e6e,
+        0x4262,
+        [0xba, 0x65, 0x62, 0xb9, 0x23, 0x6e, 0x54, 0x95],
+    );
+}
+
+/// Represents an `EFI_TIMESTAMP_PROPERTIES`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampProperties {
+    frequency: u64,
+    end_value: u64,
+}
+
+impl TimestampProperties {
+    /// Returns the frequency of the timestamp counter in Hz.
+    pub fn frequency(&self) -> u64 {
+        self.frequency
+    }
+
+    /// Returns the value that the timestamp counter will reach before it rolls over to zero.
+    pub fn end_value(&self) -> u64 {
+        self.end_value
+    }
+}
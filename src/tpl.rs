@@ -0,0 +1,83 @@
+use crate::{boot_services_exited, system_table, Tpl};
+use core::cmp::{max, min};
+use core::marker::PhantomData;
+
+/// A shortcut to [`TplGuard::new()`].
+pub fn raise_tpl(tpl: Tpl) -> TplGuard {
+    TplGuard::new(tpl)
+}
+
+/// An RAII implementation of a raised task priority level. The previous task priority level will be
+/// restored when this guard is dropped.
+///
+/// This can be used to protect a shared state from the event notification functions by raising the
+/// task priority level to the same or higher level than the notification functions.
+///
+/// Dropping a guard will never raise the task priority level, even if the guards was dropped in a
+/// different order than their creation.
+#[must_use]
+pub struct TplGuard {
+    old: Tpl,
+    phantom: PhantomData<*const ()>, // For !Send and !Sync.
+}
+
+impl TplGuard {
+    /// Raises the task priority level to `tpl`.
+    ///
+    /// The task priority level will not be changed if `tpl` is lower than the current task priority
+    /// level.
+    pub fn new(tpl: Tpl) -> Self {
+        let bs = system_table().boot_services();
+
+        // RaiseTPL() does not allow to lower the task priority level so we need to get the current
+        // level first.
+        let old = unsafe { bs.raise_tpl(Tpl::HIGH_LEVEL) };
+
+        unsafe { bs.restore_tpl(max(old, tpl)) };
+
+        Self {
+            old,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the task priority level before this guard was created.
+    pub fn previous(&self) -> Tpl {
+        self.old
+    }
+}
+
+impl Drop for TplGuard {
+    fn drop(&mut self) {
+        if boot_services_exited() {
+            return;
+        }
+
+        let bs = system_table().boot_services();
+
+        // RestoreTPL() does not allow to raise the task priority level so we need to get the current
+        // level first in case of the guards was dropped in a different order.
+        let current = unsafe { bs.raise_tpl(Tpl::HIGH_LEVEL) };
+
+        unsafe { bs.restore_tpl(min(current, self.old)) };
+    }
+}
+
+/// An implementation of [`critical_section::Impl`] by raising the task priority level to
+/// [`Tpl::HIGH_LEVEL`].
+#[cfg(feature = "critical-section")]
+struct CriticalSection;
+
+#[cfg(feature = "critical-section")]
+critical_section::set_impl!(CriticalSection);
+
+#[cfg(feature = "critical-section")]
+unsafe impl critical_section::Impl for CriticalSection {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        system_table().boot_services().raise_tpl(Tpl::HIGH_LEVEL).0
+    }
+
+    unsafe fn release(state: critical_section::RawRestoreState) {
+        system_table().boot_services().restore_tpl(Tpl(state));
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{system_table, EfiStr, Status};
+use core::time::Duration;
+
+/// Disables the watchdog timer.
+///
+/// The firmware will arm the watchdog timer for 5 minutes before starting the boot application and
+/// reset the system once it expired.
+pub fn disable_watchdog() -> Result<(), Status> {
+    system_table()
+        .boot_services()
+        .set_watchdog_timer(0, 0, None)
+}
+
+/// Re-arms the watchdog timer to reset the system after `timeout`. The timeout will be rounded up to
+/// seconds.
+///
+/// `code` and `data` will be logged when the watchdog timer expired. `code` from `0x0000` to
+/// `0xFFFF` are reserved for the firmware.
+pub fn set_watchdog(timeout: Duration, code: u64, data: Option<&EfiStr>) -> Result<(), Status> {
+    let mut secs = timeout.as_secs();
+
+    if timeout.subsec_nanos() != 0 {
+        secs += 1;
+    }
+
+    // Zero will disable the watchdog timer.
+    if secs == 0 {
+        return Err(Status::INVALID_PARAMETER);
+    }
+
+    system_table().boot_services().set_watchdog_timer(
+        secs.try_into().unwrap_or(usize::MAX),
+        code,
+        data,
+    )
+}
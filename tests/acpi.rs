@@ -0,0 +1,20 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn tables() {
+    use zfi::{Fadt, Madt, MadtEntry, Rsdp};
+
+    let rsdp = Rsdp::find().unwrap();
+    let fadt = rsdp.table::<Fadt>().unwrap();
+
+    assert_eq!(fadt.dsdt().unwrap().signature(), *b"DSDT");
+
+    if cfg!(target_arch = "x86_64") {
+        let madt = rsdp.table::<Madt>().unwrap();
+
+        assert!(madt
+            .entries()
+            .any(|e| matches!(e, MadtEntry::LocalApic { .. })));
+    }
+}
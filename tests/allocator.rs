@@ -0,0 +1,44 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn pool_allocator() {
+    use core::alloc::{GlobalAlloc, Layout};
+    use zfi::{MemoryType, PoolAllocator, PoolOptions};
+
+    let alloc = PoolAllocator::new(MemoryType::LoaderData, PoolOptions::DEBUG);
+    let layout = Layout::from_size_align(100, 64).unwrap();
+
+    // Allocate.
+    let ptr = unsafe { alloc.alloc(layout) };
+
+    assert!(!ptr.is_null());
+    assert_eq!(ptr as usize % 64, 0);
+    assert_eq!(alloc.stats().live(), 100);
+    assert_eq!(alloc.stats().allocations(), 1);
+
+    // Shrink, which should be done in-place.
+    unsafe { ptr.write_bytes(0xAB, 100) };
+
+    let new = unsafe { alloc.realloc(ptr, layout, 50) };
+
+    assert_eq!(new, ptr);
+    assert_eq!(alloc.stats().live(), 50);
+    assert_eq!(alloc.stats().peak(), 100);
+
+    // Grow.
+    let layout = Layout::from_size_align(50, 64).unwrap();
+    let ptr = unsafe { alloc.realloc(new, layout, 4096) };
+
+    assert!(!ptr.is_null());
+    assert_eq!(ptr as usize % 64, 0);
+    assert_eq!(unsafe { ptr.read() }, 0xAB);
+    assert_eq!(alloc.stats().live(), 4096);
+    assert_eq!(alloc.stats().allocations(), 1);
+
+    // Free.
+    unsafe { alloc.dealloc(ptr, Layout::from_size_align(4096, 64).unwrap()) };
+
+    assert_eq!(alloc.stats().live(), 0);
+    assert_eq!(alloc.stats().allocations(), 0);
+}
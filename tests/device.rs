@@ -0,0 +1,42 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn find() {
+    use zfi::{current_image, Device, Protocol, SimpleFileSystem};
+
+    let device = current_image().proto().device();
+    let all = Device::all().unwrap();
+    let found = Device::find::<SimpleFileSystem>().unwrap();
+
+    assert!(all.iter().any(|&d| core::ptr::eq(d, device)));
+    assert!(found.iter().any(|&d| core::ptr::eq(d, device)));
+    assert!(device.protocols().unwrap().contains(&SimpleFileSystem::ID));
+}
+
+#[test]
+#[qemu]
+fn open() {
+    use zfi::{current_image, OpenProtocolAttributes, SimpleFileSystem};
+
+    let device = current_image().proto().device();
+    let fs = device
+        .open::<SimpleFileSystem>(OpenProtocolAttributes::BY_HANDLE_PROTOCOL)
+        .unwrap();
+
+    assert!(device.supports::<SimpleFileSystem>());
+    assert!(fs.open().is_ok());
+    assert!(device
+        .open::<SimpleFileSystem>(OpenProtocolAttributes::TEST_PROTOCOL)
+        .is_err());
+}
+
+#[test]
+#[qemu]
+fn connect() {
+    use zfi::{Device, SimpleFileSystem};
+
+    Device::connect_all().unwrap();
+
+    assert!(!Device::find::<SimpleFileSystem>().unwrap().is_empty());
+}
@@ -0,0 +1,72 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn binding() {
+    use alloc::boxed::Box;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use zfi::{
+        current_image, install_driver, Device, DriverBinding, Guid, InstalledProtocol, Path,
+        Protocol, Status,
+    };
+
+    #[repr(C)]
+    struct Custom {
+        value: u32,
+    }
+
+    unsafe impl Protocol for Custom {
+        const ID: Guid = Guid::new(
+            0x0f3a6c52,
+            0x7d21,
+            0x4e0b,
+            [0xb5, 0x83, 0x61, 0x2e, 0x9a, 0x4d, 0x17, 0xc8],
+        );
+    }
+
+    static SUPPORTED: AtomicUsize = AtomicUsize::new(0);
+    static START: AtomicUsize = AtomicUsize::new(0);
+    static STOP: AtomicUsize = AtomicUsize::new(0);
+
+    struct Driver;
+
+    impl DriverBinding for Driver {
+        fn supported(&self, controller: &Device, _: Option<&Path>) -> Result<(), Status> {
+            SUPPORTED.fetch_add(1, Ordering::Relaxed);
+
+            match controller.supports::<Custom>() {
+                true => Ok(()),
+                false => Err(Status::UNSUPPORTED),
+            }
+        }
+
+        fn start(&self, controller: &Device, _: Option<&Path>) -> Result<(), Status> {
+            assert_eq!(controller.get::<Custom>().unwrap().value, 3);
+            START.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn stop(&self, _: &Device, children: &[&Device]) -> Result<(), Status> {
+            assert!(children.is_empty());
+            STOP.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    let installed = InstalledProtocol::install(None, Box::new(Custom { value: 3 })).unwrap();
+    let controller = installed.handle();
+
+    install_driver(Box::new(Driver)).unwrap();
+    controller
+        .connect(Some(&[current_image()]), None, false)
+        .unwrap();
+
+    assert_ne!(SUPPORTED.load(Ordering::Relaxed), 0);
+    assert_eq!(START.load(Ordering::Relaxed), 1);
+    assert_eq!(STOP.load(Ordering::Relaxed), 0);
+
+    controller.disconnect(Some(current_image()), None).unwrap();
+
+    assert_eq!(START.load(Ordering::Relaxed), 1);
+    assert_eq!(STOP.load(Ordering::Relaxed), 1);
+}
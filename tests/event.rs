@@ -0,0 +1,44 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn timer() {
+    use core::time::Duration;
+    use zfi::{wait_any, Event};
+
+    let timer = Event::timer().unwrap();
+
+    assert_eq!(timer.check().unwrap(), false);
+
+    timer.set_relative(Duration::from_millis(10)).unwrap();
+
+    assert_eq!(wait_any(&[&timer]).unwrap(), 0);
+}
+
+#[test]
+#[qemu]
+fn notify() {
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+    use zfi::{Event, EventType, Tpl};
+
+    let count = Rc::new(Cell::new(0));
+    let event = Event::new(
+        EventType::NOTIFY_SIGNAL,
+        Tpl::CALLBACK,
+        Some(Box::new({
+            let count = count.clone();
+            move |_| count.set(count.get() + 1)
+        })),
+    )
+    .unwrap();
+
+    event.signal().unwrap();
+
+    assert_eq!(count.get(), 1);
+
+    drop(event);
+
+    assert_eq!(Rc::strong_count(&count), 1);
+}
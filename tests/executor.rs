@@ -0,0 +1,76 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn timer() {
+    use core::time::Duration;
+    use zfi::{block_on, Timer};
+
+    block_on(async {
+        Timer::after(Duration::from_millis(10))
+            .unwrap()
+            .await
+            .unwrap();
+        Timer::after(Duration::from_millis(10))
+            .unwrap()
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+#[qemu]
+fn signaled() {
+    use zfi::{block_on, Event};
+
+    let event = Event::timer().unwrap();
+
+    event.signal().unwrap();
+
+    assert_eq!(block_on(event.signaled()), Ok(()));
+    assert_eq!(event.check(), Ok(false));
+}
+
+#[test]
+#[qemu]
+fn wake() {
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+    use core::future::poll_fn;
+    use core::task::{Poll, Waker};
+    use core::time::Duration;
+    use zfi::{block_on, Event, EventType, Tpl};
+
+    let done = Rc::new(Cell::new(false));
+    let waker: Rc<Cell<Option<Waker>>> = Rc::new(Cell::new(None));
+    let timer = Event::new(
+        EventType::TIMER | EventType::NOTIFY_SIGNAL,
+        Tpl::CALLBACK,
+        Some(Box::new({
+            let done = done.clone();
+            let waker = waker.clone();
+
+            move |_| {
+                done.set(true);
+
+                if let Some(w) = waker.take() {
+                    w.wake();
+                }
+            }
+        })),
+    )
+    .unwrap();
+
+    timer.set_relative(Duration::from_millis(10)).unwrap();
+
+    // The future does not have any event to wait for so it relies on the waker.
+    block_on(poll_fn(|cx| {
+        waker.set(Some(cx.waker().clone()));
+
+        match done.get() {
+            true => Poll::Ready(()),
+            false => Poll::Pending,
+        }
+    }));
+}
@@ -0,0 +1,28 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn chosen() {
+    use zfi::{Fdt, FdtBuilder, FdtError};
+
+    // The firmware may not provide the device tree (e.g. on x86 or it was configured to use ACPI).
+    let fdt = match Fdt::find() {
+        Ok(v) => v,
+        Err(FdtError::NotFound) => return,
+        Err(e) => panic!("{e}"),
+    };
+
+    assert!(fdt.find_node("/memory").unwrap().reg().unwrap().count() > 0);
+
+    // Patch.
+    let mut b = FdtBuilder::from_fdt(&fdt);
+
+    b.set_bootargs("console=ttyAMA0").unwrap();
+    b.set_initrd(0x48000000, 0x49000000).unwrap();
+
+    let data = b.build();
+    let fdt = Fdt::new(&data).unwrap();
+
+    assert_eq!(fdt.bootargs(), Some("console=ttyAMA0"));
+    assert_eq!(fdt.initrd(), Some((0x48000000, 0x49000000)));
+}
@@ -21,3 +21,27 @@ fn proto() {
     assert_eq!(proto.device().file_system().is_some(), true);
     assert_eq!(*proto.file_path(), path);
 }
+
+#[test]
+#[qemu]
+fn load() {
+    use zfi::{current_image, str, Image};
+
+    let proto = current_image().proto();
+    let path = proto
+        .device()
+        .path()
+        .unwrap()
+        .join_media_file_path(proto.file_path().to_media_file_path().unwrap());
+    let mut child = Image::load_from_path(&path).unwrap();
+
+    child.set_command_line(str!("hello"));
+
+    assert!(!child.proto().image_base().is_null());
+    assert_eq!(
+        child.proto().load_options(),
+        &[b'h', 0, b'e', 0, b'l', 0, b'l', 0, b'o', 0, 0, 0]
+    );
+
+    child.unload().unwrap();
+}
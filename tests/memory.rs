@@ -0,0 +1,67 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn memory_map() {
+    use zfi::{get_memory_map, MemoryDescriptor, MemoryType};
+
+    let map = get_memory_map().unwrap();
+
+    assert!(map.descriptor_size() >= size_of::<MemoryDescriptor>());
+    assert_eq!(map.iter().count(), map.len());
+    assert!(map
+        .iter()
+        .any(|d| d.ty() == Some(MemoryType::ConventionalMemory) && d.page_count() != 0));
+}
+
+#[test]
+#[qemu]
+fn memory_regions() {
+    use zfi::{get_memory_map, RegionKind};
+
+    let map = get_memory_map().unwrap();
+    let regions = map.regions();
+
+    assert!(!regions.is_empty());
+    assert!(regions.windows(2).all(|w| w[0].end() <= w[1].start()));
+    assert!(map.usable_size() != 0);
+    assert!(map.largest_free_below(u64::MAX).is_some());
+    assert_eq!(map.to_e820().len(), regions.len());
+    assert!(regions.iter().any(|r| r.kind() == RegionKind::Usable));
+}
+
+#[test]
+#[qemu]
+fn page_allocator() {
+    use core::alloc::Layout;
+    use zfi::{MemoryType, PageAllocator, PageLimit, Status};
+
+    // Allocate 2 MiB aligned pages below 4 GiB.
+    let alloc = PageAllocator::new(MemoryType::LoaderData, PageLimit::MaxAddress(0xFFFFFFFF));
+    let layout = Layout::from_size_align(0x3000, 0x200000).unwrap();
+    let mut pages = alloc.allocate_zeroed(layout).unwrap();
+
+    assert_eq!(pages.addr() % 0x200000, 0);
+    assert!(pages.addr() + pages.len() <= 0x100000000);
+    assert_eq!(pages.len(), 0x3000);
+    assert!(pages.iter().all(|&b| b == 0));
+    assert_eq!(pages.as_uninit_slice_mut::<u64>().len(), 0x600);
+
+    // Allocate at the same address.
+    let addr = pages.addr() as u64;
+
+    drop(pages);
+
+    let alloc = PageAllocator::new(MemoryType::LoaderData, PageLimit::Address(addr));
+    let pages = alloc.allocate(layout).unwrap();
+
+    assert_eq!(pages.addr() as u64, addr);
+
+    // Check misaligned address.
+    let alloc = PageAllocator::new(MemoryType::LoaderData, PageLimit::Address(addr + 0x1000));
+
+    assert_eq!(
+        alloc.allocate(layout).err(),
+        Some(Status::INVALID_PARAMETER)
+    );
+}
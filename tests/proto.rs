@@ -0,0 +1,65 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn install() {
+    use alloc::boxed::Box;
+    use zfi::{Device, Guid, InstalledProtocol, Protocol};
+
+    #[repr(C)]
+    struct Custom {
+        value: u32,
+    }
+
+    unsafe impl Protocol for Custom {
+        const ID: Guid = Guid::new(
+            0x2c7d5b39,
+            0x1c7e,
+            0x4b8f,
+            [0x9c, 0x4e, 0x4a, 0x6f, 0x1b, 0x51, 0x2d, 0x0e],
+        );
+    }
+
+    let installed = InstalledProtocol::install(None, Box::new(Custom { value: 7 })).unwrap();
+    let handle = installed.handle();
+
+    assert_eq!(handle.get::<Custom>().unwrap().value, 7);
+    assert_eq!(Device::find::<Custom>().unwrap().len(), 1);
+
+    drop(installed);
+
+    assert_eq!(Device::find::<Custom>().unwrap().len(), 0);
+}
+
+#[test]
+#[qemu]
+fn notify() {
+    use alloc::boxed::Box;
+    use zfi::{Guid, InstalledProtocol, Protocol, ProtocolNotify};
+
+    #[repr(C)]
+    struct Custom {
+        value: u32,
+    }
+
+    unsafe impl Protocol for Custom {
+        const ID: Guid = Guid::new(
+            0x6b0e2e8a,
+            0x43a2,
+            0x4d1c,
+            [0x8a, 0x1f, 0x25, 0x9d, 0x3e, 0x7c, 0x40, 0x11],
+        );
+    }
+
+    let notify = ProtocolNotify::new::<Custom>().unwrap();
+
+    assert_eq!(notify.event().check().unwrap(), false);
+    assert_eq!(notify.handles().count(), 0);
+
+    let installed = InstalledProtocol::install(None, Box::new(Custom { value: 1 })).unwrap();
+    let mut handles = notify.handles();
+
+    assert_eq!(notify.event().check().unwrap(), true);
+    assert!(core::ptr::eq(handles.next().unwrap(), installed.handle()));
+    assert!(handles.next().is_none());
+}
@@ -0,0 +1,49 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn variable() {
+    use zfi::{str, system_table, Guid, Status, VariableAttributes, GLOBAL_VARIABLE};
+
+    let rt = system_table().runtime_services();
+    let vendor = Guid::new(
+        0x3a5c2e4f,
+        0x7d1b,
+        0x4c8e,
+        [0x9a, 0x61, 0x2f, 0x0e, 0x8b, 0x4d, 0x13, 0x77],
+    );
+    let name = str!("ZfiTest");
+    let attrs = VariableAttributes::BOOTSERVICE_ACCESS | VariableAttributes::RUNTIME_ACCESS;
+
+    // Set and get.
+    rt.set_variable(name, &vendor, attrs, &[1, 2, 3]).unwrap();
+
+    let (a, data) = rt.get_variable(name, &vendor).unwrap();
+
+    assert_eq!(a, attrs);
+    assert_eq!(data, [1, 2, 3]);
+
+    // Enumerate.
+    assert!(rt
+        .variable_names()
+        .map(Result::unwrap)
+        .any(|(n, v)| v == vendor && *n == *name));
+
+    assert!(rt
+        .variable_names()
+        .map(Result::unwrap)
+        .any(|(_, v)| v == GLOBAL_VARIABLE));
+
+    // Delete.
+    rt.delete_variable(name, &vendor).unwrap();
+
+    assert_eq!(
+        rt.get_variable(name, &vendor).err(),
+        Some(Status::NOT_FOUND)
+    );
+
+    // Query.
+    let info = rt.query_variable_info(attrs).unwrap();
+
+    assert!(info.max_storage() >= info.remaining_storage());
+}
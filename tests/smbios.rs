@@ -0,0 +1,13 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn system() {
+    use zfi::{Smbios, SystemInfo};
+
+    let smbios = Smbios::find().unwrap();
+    let sys: SystemInfo = smbios.structure().unwrap();
+
+    assert!(sys.manufacturer().is_some());
+    assert!(smbios.structures().count() > 1);
+}
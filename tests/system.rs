@@ -0,0 +1,32 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn config_table() {
+    use zfi::{system_table, Guid};
+
+    static TABLE: u32 = 0x12345678;
+
+    let st = system_table();
+    let bs = st.boot_services();
+    let guid = Guid::new(
+        0x5c4f7a1e,
+        0x3b2d,
+        0x4e8f,
+        [0x9a, 0x61, 0x2d, 0x7c, 0x0b, 0x4e, 0x15, 0xa3],
+    );
+
+    assert!(!st.firmware_vendor().is_empty());
+    assert!(st.find_config_table(&guid).is_none());
+
+    unsafe { bs.install_configuration_table(&guid, &TABLE as *const u32 as *const ()) }.unwrap();
+
+    assert_eq!(
+        st.find_config_table(&guid),
+        Some(&TABLE as *const u32 as *const ())
+    );
+
+    unsafe { bs.install_configuration_table(&guid, core::ptr::null()) }.unwrap();
+
+    assert!(st.find_config_table(&guid).is_none());
+}
@@ -0,0 +1,37 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn sleep() {
+    use core::time::Duration;
+    use zfi::{sleep, Instant, PreciseInstant};
+
+    let start = Instant::now().unwrap();
+    let precise = PreciseInstant::now();
+
+    sleep(Duration::from_millis(1500));
+
+    assert!(Instant::now().unwrap() > start);
+
+    if let Some(v) = precise {
+        assert!(v.elapsed() >= Duration::from_millis(1500));
+    }
+}
+
+#[test]
+#[qemu]
+fn rtc() {
+    use alloc::string::ToString;
+    use zfi::{system_table, Time};
+
+    let (now, caps) = system_table().runtime_services().get_time().unwrap();
+
+    assert!(now.validate().is_ok());
+    assert!(now.year() >= 2024);
+    assert!(now.to_unix().unwrap() > 0);
+    assert!(caps.resolution() != 0);
+    assert!(now
+        .to_string()
+        .starts_with(&alloc::format!("{:04}-", now.year())));
+    assert!(Time::now().unwrap().to_unix().unwrap() >= now.to_unix().unwrap());
+}
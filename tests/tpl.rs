@@ -0,0 +1,18 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn guard() {
+    use zfi::{raise_tpl, Tpl};
+
+    let notify = raise_tpl(Tpl::NOTIFY);
+    let callback = raise_tpl(Tpl::CALLBACK);
+
+    assert_eq!(notify.previous(), Tpl::APPLICATION);
+    assert_eq!(callback.previous(), Tpl::NOTIFY);
+
+    drop(notify);
+    drop(callback);
+
+    assert_eq!(raise_tpl(Tpl::APPLICATION).previous(), Tpl::APPLICATION);
+}
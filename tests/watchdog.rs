@@ -0,0 +1,17 @@
+use zfi_testing::qemu;
+
+#[test]
+#[qemu]
+fn watchdog() {
+    use core::time::Duration;
+    use zfi::{disable_watchdog, set_watchdog, str, Status};
+
+    set_watchdog(Duration::from_secs(600), 0x10000, Some(str!("zfi"))).unwrap();
+
+    assert_eq!(
+        set_watchdog(Duration::ZERO, 0x10000, None),
+        Err(Status::INVALID_PARAMETER)
+    );
+
+    disable_watchdog().unwrap();
+}